@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+use dlauncher::launcher::util::{
+  config::Config,
+  theme::{Theme, BUILTIN_THEMES},
+};
+
+const USAGE: &str = "Usage:
+  dlauncher-themes list              List installed and built-in themes
+  dlauncher-themes install <name>    Reinstall a built-in theme, `all` reinstalls every one
+  dlauncher-themes import [dir]      Import Ulauncher themes, defaults to ~/.config/ulauncher/user-themes
+    --replace                        Overwrite installed themes with the same name";
+
+fn main() {
+  let args = std::env::args().skip(1).collect::<Vec<String>>();
+  let config = Config::read();
+
+  let replace = args.iter().any(|arg| arg == "--replace");
+  let args = args
+    .iter()
+    .map(String::as_str)
+    .filter(|arg| *arg != "--replace")
+    .collect::<Vec<&str>>();
+
+  match args[..] {
+    ["list"] => list(&config),
+    ["install", name] => install(&config, name),
+    ["import"] => {
+      let home = PathBuf::from(std::env::var("HOME").expect("you are homeless"));
+      import(&config, &home.join(".config/ulauncher/user-themes"), replace);
+    }
+    ["import", dir] => import(&config, &PathBuf::from(dir), replace),
+    _ => {
+      eprintln!("{}", USAGE);
+      std::process::exit(1);
+    }
+  }
+}
+
+fn list(config: &Config) {
+  let installed = Theme::installed(config);
+
+  for theme in &installed {
+    let mut notes = vec![];
+    if theme.inner.name == config.launcher.color_theme {
+      notes.push("current");
+    }
+    if config.launcher.dark_theme.as_ref() == Some(&theme.inner.name) {
+      notes.push("dark");
+    }
+    if BUILTIN_THEMES
+      .iter()
+      .any(|(name, _)| *name == theme.inner.name)
+    {
+      notes.push("built-in");
+    }
+
+    if notes.is_empty() {
+      println!("{:16} {}", theme.inner.name, theme.inner.display_name);
+    } else {
+      println!(
+        "{:16} {} ({})",
+        theme.inner.name,
+        theme.inner.display_name,
+        notes.join(", ")
+      );
+    }
+  }
+
+  for (name, _) in BUILTIN_THEMES {
+    if !installed.iter().any(|theme| theme.inner.name == *name) {
+      println!(
+        "{:16} not installed, run `dlauncher-themes install {}`",
+        name, name
+      );
+    }
+  }
+}
+
+fn install(config: &Config, name: &str) {
+  let names = if name == "all" {
+    BUILTIN_THEMES.iter().map(|(name, _)| *name).collect()
+  } else {
+    vec![name]
+  };
+
+  for name in names {
+    match Theme::install_builtin(config, name) {
+      Ok(true) => println!("Installed {}", name),
+      Ok(false) => {
+        eprintln!(
+          "There is no built-in theme called {:?}, built-in themes are: {}",
+          name,
+          BUILTIN_THEMES
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>()
+            .join(", ")
+        );
+        std::process::exit(1);
+      }
+      Err(err) => {
+        eprintln!("Can't install {}: {}", name, err);
+        std::process::exit(1);
+      }
+    }
+  }
+}
+
+fn import(config: &Config, dir: &Path, replace: bool) {
+  // a single theme, or a directory of themes like Ulauncher's user-themes
+  let themes = if dir.join("manifest.json").is_file() {
+    vec![dir.to_path_buf()]
+  } else {
+    match dir.read_dir() {
+      Ok(entries) => entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect(),
+      Err(err) => {
+        eprintln!("Can't read {}: {}", dir.display(), err);
+        std::process::exit(1);
+      }
+    }
+  };
+
+  let mut failed = false;
+  for theme in themes {
+    match Theme::import(config, &theme, replace) {
+      Ok(name) => println!("Imported {}", name),
+      Err(err) => {
+        eprintln!("Skipped {}: {}", theme.display(), err);
+        failed = true;
+      }
+    }
+  }
+
+  if failed {
+    std::process::exit(1);
+  }
+}
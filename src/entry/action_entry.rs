@@ -0,0 +1,71 @@
+use std::{fmt, rc::Rc};
+
+use gtk::gdk_pixbuf::Pixbuf;
+
+use crate::launcher::{util::icon::load_icon, window::Window};
+
+pub type ActionFn = Rc<dyn Fn(Window)>;
+/// Loads the icon of an action at the given size.
+pub type ActionIconFn = Rc<dyn Fn(i32) -> Pixbuf>;
+
+/// One of the actions of a result, listed in the action menu (Alt+Enter or Tab).
+#[derive(Clone)]
+pub struct ActionEntry {
+  pub name: String,
+  pub description: String,
+  pub icon: ActionIconFn,
+  pub run: ActionFn,
+}
+
+impl ActionEntry {
+  pub fn new<F>(name: impl Into<String>, description: impl Into<String>, icon: &str, run: F) -> Self
+  where
+    F: Fn(Window) + 'static,
+  {
+    let icon = icon.to_string();
+    Self::with_icon(name, description, move |size| load_icon(&icon, size), run)
+  }
+
+  pub fn with_icon<I, F>(
+    name: impl Into<String>,
+    description: impl Into<String>,
+    icon: I,
+    run: F,
+  ) -> Self
+  where
+    I: Fn(i32) -> Pixbuf + 'static,
+    F: Fn(Window) + 'static,
+  {
+    Self {
+      name: name.into(),
+      description: description.into(),
+      icon: Rc::new(icon),
+      run: Rc::new(run),
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    (self.icon)(size)
+  }
+
+  pub fn execute(&self, window: Window) {
+    (self.run)(window);
+  }
+}
+
+impl fmt::Debug for ActionEntry {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ActionEntry")
+      .field("name", &self.name)
+      .field("description", &self.description)
+      .finish()
+  }
+}
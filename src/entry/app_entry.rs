@@ -1,14 +1,24 @@
 use std::path::PathBuf;
 
-use gtk::gdk_pixbuf::Pixbuf;
-use log::debug;
+use gtk::{
+  gdk_pixbuf::Pixbuf,
+  gio::{DesktopAppInfo, File},
+  prelude::*,
+};
+use log::{debug, error};
 
 use crate::{
+  entry::action_entry::ActionEntry,
   launcher::{
-    util::{icon::default_pixbuf, recent::Recent},
+    util::{
+      config::Config,
+      icon::{default_pixbuf, load_icon},
+      recent::Recent,
+    },
     window::Window,
   },
-  util::launch_detached,
+  provider::windows::Windows,
+  util::{launch_app, launch_detached, show_in_file_manager, xdg_open},
 };
 
 #[derive(Debug, Clone)]
@@ -16,27 +26,134 @@ pub struct AppEntry {
   pub name: String,
   pub description: String,
   pub file: PathBuf,
-  pub icon: Option<Pixbuf>,
+  /// Themed icon name or absolute path, loaded at the size it's shown at.
+  pub icon: Option<String>,
+  /// The command line of the app with its field codes removed.
   pub exec: Vec<String>,
+  /// Other names the app is matched by, set in the `[apps.overrides]` config section.
+  pub keywords: Vec<String>,
   pub terminal: bool,
+  /// `StartupWMClass` of the desktop entry, the WM_CLASS the app's windows are expected to have.
+  pub startup_wm_class: Option<String>,
+  /// The desktop entry itself, used to launch the app.
+  pub info: DesktopAppInfo,
 }
 
 impl AppEntry {
+  /// Switch to an open window of the app when `focus_existing` is enabled, launch it otherwise.
   pub fn execute(&self, window: Window) {
-    let spawn_args = if self.terminal && window.config.launcher.terminal_command.as_ref().is_some() {
-      let cmd = window.config.launcher.terminal_command.as_ref().unwrap();
-      let full = shell_words::join(&self.exec);
-      let cmd = cmd.replace("{}", &full);
+    let focus_existing = window
+      .config
+      .window_switcher()
+      .map(|switcher| switcher.focus_existing)
+      .unwrap_or(false);
 
-      shell_words::split(&cmd).unwrap()
-    } else {
-      self.exec.clone()
-    };
+    if focus_existing {
+      if let Some(existing) = self
+        .startup_wm_class
+        .as_deref()
+        .and_then(Windows::find_class)
+      {
+        Windows::activate(&existing);
+        self.save_recent(&window);
+        return;
+      }
+    }
 
-    debug!("Attempting to launch {:?}", spawn_args);
+    self.execute_with(window, &[]);
+  }
 
+  /// Launch the app with `uris` substituted into its field codes, local files are passed as
+  /// `file://` uris.
+  pub fn execute_with(&self, window: Window, uris: &[String]) {
+    match window.config.launcher.terminal_command.as_ref() {
+      Some(terminal) if self.terminal => self.launch_in_terminal(terminal, uris),
+      _ => {
+        debug!("Attempting to launch {} {:?}", self.file.display(), uris);
+        let uris = uris.iter().map(|uri| uri.as_str()).collect::<Vec<&str>>();
+        if let Err(err) = launch_app(&self.info, &uris) {
+          error!("Failed to launch {}: {}", self.file.display(), err);
+          return;
+        }
+      }
+    }
+
+    self.save_recent(&window);
+  }
+
+  /// Run the app's command through `terminal_command`, `{}` is replaced with the command.
+  fn launch_in_terminal(&self, terminal: &str, uris: &[String]) {
+    let mut exec = self.exec.clone();
+    exec.extend(uris.iter().map(|uri| {
+      File::for_uri(uri)
+        .path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| uri.to_string())
+    }));
+    let full = shell_words::join(&exec);
+    let cmd = terminal.replace("{}", &full);
+    let spawn_args = shell_words::split(&cmd).unwrap();
+
+    debug!("Attempting to launch {:?}", spawn_args);
     launch_detached(spawn_args, vec![]);
+  }
+
+  /// Secondary actions listed in the action menu.
+  pub fn actions(&self, config: &Config) -> Vec<ActionEntry> {
+    let mut actions = vec![];
+
+    if let Some(terminal) = config.launcher.terminal_command.clone() {
+      let app = self.clone();
+      actions.push(ActionEntry::new(
+        "Launch in terminal",
+        "Run the command through terminal_command",
+        "utilities-terminal",
+        move |window| {
+          app.launch_in_terminal(&terminal, &[]);
+          app.save_recent(&window);
+        },
+      ));
+    }
+
+    if let Some(path) = self.info.filename() {
+      let desktop_file = path.clone();
+      actions.push(ActionEntry::new(
+        "Open desktop entry",
+        path.display().to_string(),
+        "text-x-generic",
+        move |_| xdg_open(vec![&desktop_file.display().to_string()], vec![]),
+      ));
+
+      actions.push(ActionEntry::new(
+        "Show in file manager",
+        path.display().to_string(),
+        "system-file-manager",
+        move |_| show_in_file_manager(&path),
+      ));
+    }
+
+    let id = self.file.display().to_string();
+    actions.push(ActionEntry::new(
+      "Hide from results",
+      format!("Stop showing {} in the results", self.name),
+      "view-conceal-symbolic",
+      move |window| {
+        window.config.hide_app(&id);
+        window.state.hidden_apps.lock().unwrap().push(id.clone());
+        window
+          .state
+          .apps
+          .lock()
+          .unwrap()
+          .retain(|app| app.file.display().to_string() != id);
+        window.refresh_results();
+      },
+    ));
+
+    actions
+  }
 
+  fn save_recent(&self, window: &Window) {
     let mut recents = window.state.recents.lock().unwrap();
     let recent = recents.iter_mut().find(|r| r.file == self.file);
     if let Some(recent) = recent {
@@ -52,10 +169,10 @@ impl AppEntry {
     Recent::recents_to_file(recents.to_vec(), &window.config.recents());
   }
 
-  pub fn icon(&self) -> Pixbuf {
+  pub fn icon(&self, size: i32) -> Pixbuf {
     match &self.icon {
-      Some(icon) => icon.clone(),
-      None => default_pixbuf(40),
+      Some(icon) => load_icon(icon, size),
+      None => default_pixbuf(size),
     }
   }
 }
@@ -0,0 +1,41 @@
+use gtk::gdk_pixbuf::Pixbuf;
+
+use crate::{
+  launcher::util::icon::load_icon,
+  provider::calculator::Calculation,
+  util::copy_to_clipboard,
+};
+
+#[derive(Debug, Clone)]
+pub struct CalculatorEntry {
+  pub calculation: Calculation,
+  description: String,
+}
+
+impl CalculatorEntry {
+  pub fn new(calculation: Calculation) -> Self {
+    let description = calculation.expression.clone();
+
+    Self {
+      calculation,
+      description,
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.calculation.value
+  }
+
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    load_icon("accessories-calculator", size)
+  }
+
+  /// Copies the result to the clipboard.
+  pub fn execute(&self) {
+    copy_to_clipboard(&self.calculation.value);
+  }
+}
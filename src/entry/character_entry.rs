@@ -0,0 +1,69 @@
+use gtk::gdk_pixbuf::Pixbuf;
+
+use crate::{
+  entry::action_entry::ActionEntry,
+  launcher::{preview::Preview, util::icon::glyph_pixbuf, window::Window},
+  provider::characters::Character,
+  util::copy_to_clipboard,
+};
+
+#[derive(Debug, Clone)]
+pub struct CharacterEntry {
+  pub character: Character,
+  description: String,
+}
+
+impl CharacterEntry {
+  pub fn new(character: Character) -> Self {
+    let description = format!("U+{:04X}", character.code);
+
+    Self {
+      character,
+      description,
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.character.name
+  }
+
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    glyph_pixbuf(&self.character.value, size)
+  }
+
+  /// The character rendered large enough to tell similar ones apart.
+  pub fn preview(&self) -> Option<Preview> {
+    Some(Preview::Image(glyph_pixbuf(&self.character.value, 160)))
+  }
+
+  /// Copies the character to the clipboard and ranks it first next time.
+  pub fn execute(&self, window: Window) {
+    copy_to_clipboard(&self.character.value);
+
+    window
+      .state
+      .characters
+      .lock()
+      .unwrap()
+      .save_recent(&self.character.value);
+  }
+
+  /// Secondary actions listed in the action menu.
+  pub fn actions(&self) -> Vec<ActionEntry> {
+    let code = format!("U+{:04X}", self.character.code);
+    let name = self.character.name.clone();
+
+    vec![
+      ActionEntry::new("Copy code point", code.clone(), "edit-copy", move |_| {
+        copy_to_clipboard(&code)
+      }),
+      ActionEntry::new("Copy name", name.clone(), "edit-copy", move |_| {
+        copy_to_clipboard(&name)
+      }),
+    ]
+  }
+}
@@ -0,0 +1,149 @@
+use gtk::{
+  gdk::{ModifierType, SELECTION_CLIPBOARD},
+  gdk_pixbuf::{InterpType, Pixbuf},
+  glib::DateTime,
+  Clipboard,
+};
+use log::debug;
+
+use crate::{
+  entry::action_entry::ActionEntry,
+  launcher::{preview::Preview, util::icon::load_icon},
+  provider::clipboard::{ClipboardContent, ClipboardItem},
+  util::{copy_to_clipboard, launch_detached},
+};
+
+#[derive(Debug, Clone)]
+pub struct ClipboardEntry {
+  pub item: ClipboardItem,
+  /// The full image for image items, it's restored to the clipboard when the entry is executed.
+  pub image: Option<Pixbuf>,
+  pub paste_command: Option<String>,
+  name: String,
+  description: String,
+}
+
+impl ClipboardEntry {
+  pub fn new(item: ClipboardItem, image: Option<Pixbuf>, paste_command: Option<String>) -> Self {
+    let name = match &item.content {
+      ClipboardContent::Text(text) => {
+        let line = text.trim().lines().next().unwrap_or_default();
+        if line.chars().count() > 80 {
+          format!("{}…", line.chars().take(80).collect::<String>())
+        } else {
+          line.to_string()
+        }
+      }
+      ClipboardContent::Image { width, height, .. } => format!("Image {}×{}", width, height),
+    };
+
+    let description = DateTime::from_unix_local(item.time)
+      .and_then(|time| time.format("%b %e, %H:%M"))
+      .map(|time| format!("Copied {}", time))
+      .unwrap_or_else(|_| "Copied".to_string());
+
+    Self {
+      item,
+      image,
+      paste_command,
+      name,
+      description,
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    if let Some(image) = &self.image {
+      let scale = size as f64 / image.width().max(image.height()) as f64;
+      let (width, height) = (
+        ((image.width() as f64 * scale) as i32).max(1),
+        ((image.height() as f64 * scale) as i32).max(1),
+      );
+      if let Some(thumbnail) = image.scale_simple(width, height, InterpType::Bilinear) {
+        return thumbnail;
+      }
+    }
+
+    match self.item.content {
+      ClipboardContent::Text(_) => load_icon("edit-paste", size),
+      ClipboardContent::Image { .. } => load_icon("image-x-generic", size),
+    }
+  }
+
+  /// The whole text or the full image.
+  pub fn preview(&self) -> Option<Preview> {
+    match (&self.item.content, &self.image) {
+      (ClipboardContent::Text(text), _) => Some(Preview::Text(text.clone())),
+      (ClipboardContent::Image { .. }, Some(image)) => Some(Preview::Image(image.clone())),
+      (ClipboardContent::Image { .. }, None) => None,
+    }
+  }
+
+  /// Put the item back on the clipboard and paste it into the focused window with `paste_command`,
+  /// holding Shift only copies it.
+  pub fn execute(&self, modifiers: ModifierType) {
+    match (&self.item.content, &self.image) {
+      (ClipboardContent::Text(text), _) => copy_to_clipboard(text),
+      (ClipboardContent::Image { .. }, Some(image)) => {
+        let clipboard = Clipboard::get(&SELECTION_CLIPBOARD);
+        clipboard.set_image(image);
+        clipboard.store();
+      }
+      (ClipboardContent::Image { .. }, None) => return,
+    }
+
+    if let Some(command) = &self.paste_command {
+      if !modifiers.contains(ModifierType::SHIFT_MASK) {
+        // give the previously focused window time to get its focus back
+        let command = format!("sleep 0.2; {}", command);
+        debug!("Attempting to run {:?}", command);
+        launch_detached(vec!["sh", "-c", &command], vec![]);
+      }
+    }
+  }
+
+  pub fn default_action(&self) -> &str {
+    if self.paste_command.is_some() {
+      "Paste"
+    } else {
+      "Copy"
+    }
+  }
+
+  /// Secondary actions listed in the action menu.
+  pub fn actions(&self) -> Vec<ActionEntry> {
+    let mut actions = vec![];
+
+    if self.paste_command.is_some() {
+      let entry = self.clone();
+      actions.push(ActionEntry::new(
+        "Copy",
+        "Copy without pasting",
+        "edit-copy",
+        move |_| entry.execute(ModifierType::SHIFT_MASK),
+      ));
+    }
+
+    let content = self.item.content.clone();
+    actions.push(ActionEntry::new(
+      "Remove from history",
+      &self.name,
+      "edit-delete",
+      move |window| {
+        if let Some(clipboard) = &mut *window.state.clipboard.lock().unwrap() {
+          clipboard.remove(&content);
+        }
+        window.refresh_results();
+      },
+    ));
+
+    actions
+  }
+}
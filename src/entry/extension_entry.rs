@@ -1,15 +1,25 @@
 use std::fmt;
 
-use gtk::{
-  gdk_pixbuf::{Pixbuf, PixbufLoader},
-  prelude::*,
-};
+use gtk::gdk_pixbuf::Pixbuf;
 
 use crate::{
-  extension::response::{
-    ExtensionResponseIcon, ExtensionResponseIconType, ExtensionResponseLine, OnEnterFn,
+  entry::action_entry::ActionEntry,
+  extension::{
+    config::ExtensionConfig,
+    response::{
+      ExtensionResponseAction, ExtensionResponseIcon, ExtensionResponseIconType,
+      ExtensionResponseLine, OnEnterFn,
+    },
+    ExtensionContext,
+  },
+  launcher::{
+    preview::Preview,
+    util::{
+      config::Config,
+      icon::{load_icon, load_svg},
+    },
+    window::Window,
   },
-  launcher::util::icon::load_icon,
 };
 
 pub struct ExtensionEntry {
@@ -18,6 +28,10 @@ pub struct ExtensionEntry {
   pub description: String,
   pub icon: ExtensionResponseIcon,
   pub on_enter: OnEnterFn,
+  pub actions: Vec<ExtensionResponseAction>,
+  pub hint: Option<String>,
+  pub badge: Option<String>,
+  pub preview: Option<Preview>,
 }
 
 impl ExtensionEntry {
@@ -28,21 +42,56 @@ impl ExtensionEntry {
       description: line.description,
       icon: line.icon,
       on_enter: line.on_enter,
+      actions: line.actions,
+      hint: line.hint,
+      badge: line.badge,
+      preview: line.preview,
     }
   }
 
-  pub fn icon(&self) -> Pixbuf {
-    match self.icon.type_ {
-      ExtensionResponseIconType::ThemedIcon => load_icon(&self.icon.value, 40),
-      ExtensionResponseIconType::SVGStringIcon => {
-        let loader = PixbufLoader::new();
-        loader.write(self.icon.value.as_bytes()).unwrap();
-        loader.close().unwrap();
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    response_icon(&self.icon, size)
+  }
 
-        loader.pixbuf().unwrap()
-      }
+  /// Call `on_enter` of the line or one of its actions.
+  pub fn run(&self, on_enter: &OnEnterFn, window: Window) {
+    if let Some(on_enter) = on_enter.as_ref() {
+      let config = Config::read();
+      on_enter(ExtensionContext {
+        name: self.extension_name.clone(),
+        window,
+        input: None,
+        config: ExtensionConfig::new(&config, &self.extension_name),
+      })
     }
   }
+
+  /// The alternate actions defined by the extension.
+  pub fn actions(&self) -> Vec<ActionEntry> {
+    self
+      .actions
+      .iter()
+      .map(|action| {
+        let entry = self.clone();
+        let on_enter = action.on_enter.clone();
+        let icon = action.icon.clone();
+
+        ActionEntry::with_icon(
+          &action.name,
+          &action.description,
+          move |size| response_icon(&icon, size),
+          move |window| entry.run(&on_enter, window),
+        )
+      })
+      .collect()
+  }
+}
+
+fn response_icon(icon: &ExtensionResponseIcon, size: i32) -> Pixbuf {
+  match icon.type_ {
+    ExtensionResponseIconType::ThemedIcon => load_icon(&icon.value, size),
+    ExtensionResponseIconType::SVGStringIcon => load_svg(&icon.value, size),
+  }
 }
 
 impl fmt::Debug for ExtensionEntry {
@@ -52,6 +101,9 @@ impl fmt::Debug for ExtensionEntry {
       .field("name", &self.name)
       .field("description", &self.description)
       .field("icon", &self.icon)
+      .field("actions", &self.actions)
+      .field("hint", &self.hint)
+      .field("badge", &self.badge)
       .finish()
   }
 }
@@ -64,6 +116,10 @@ impl Clone for ExtensionEntry {
       description: self.description.clone(),
       icon: self.icon.clone(),
       on_enter: self.on_enter.clone(),
+      actions: self.actions.clone(),
+      hint: self.hint.clone(),
+      badge: self.badge.clone(),
+      preview: self.preview.clone(),
     }
   }
 }
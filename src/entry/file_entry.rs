@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use gtk::{
+  gdk::ModifierType,
+  gdk_pixbuf::Pixbuf,
+  gio::{content_type_get_icon, content_type_guess},
+};
+use log::debug;
+
+use crate::{
+  entry::action_entry::ActionEntry,
+  launcher::{preview::Preview, util::icon::load_gicon},
+  util::{copy_to_clipboard, show_in_file_manager, xdg_open},
+};
+
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+  pub path: PathBuf,
+  name: String,
+  description: String,
+}
+
+impl FileEntry {
+  pub fn new(path: PathBuf) -> Self {
+    let name = path
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| path.display().to_string());
+
+    let home = std::env::var("HOME").unwrap_or_default();
+    let parent = path
+      .parent()
+      .map(|parent| parent.display().to_string())
+      .unwrap_or_default();
+    let description = match parent.strip_prefix(&home) {
+      Some(rest) if !home.is_empty() => format!("~{}", rest),
+      _ => parent,
+    };
+
+    Self {
+      path,
+      name,
+      description,
+    }
+  }
+
+  /// Show `name` instead of the file name, e.g. the path relative to the search directory.
+  pub fn with_name(mut self, name: String) -> Self {
+    self.name = name;
+    self
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    let content_type = if self.path.is_dir() {
+      "inode/directory".into()
+    } else {
+      content_type_guess(Some(&self.path), &[]).0
+    };
+
+    load_gicon(&content_type_get_icon(&content_type), size)
+  }
+
+  pub fn preview(&self) -> Option<Preview> {
+    Some(Preview::File(self.path.clone()))
+  }
+
+  /// Open the file, holding Shift opens the containing folder instead and holding Control copies
+  /// the file's path.
+  pub fn execute(&self, modifiers: ModifierType) {
+    if modifiers.contains(ModifierType::CONTROL_MASK) {
+      self.copy_path();
+    } else if modifiers.contains(ModifierType::SHIFT_MASK) {
+      self.open_folder();
+    } else {
+      self.open();
+    }
+  }
+
+  pub fn open(&self) {
+    debug!("Opening {}", self.path.display());
+    xdg_open(vec![&self.path.display().to_string()], vec![]);
+  }
+
+  pub fn open_folder(&self) {
+    if let Some(parent) = self.path.parent() {
+      debug!("Opening {}", parent.display());
+      xdg_open(vec![&parent.display().to_string()], vec![]);
+    }
+  }
+
+  pub fn copy_path(&self) {
+    copy_to_clipboard(&self.path.display().to_string());
+  }
+
+  /// Secondary actions listed in the action menu.
+  pub fn actions(&self) -> Vec<ActionEntry> {
+    let (show, folder, copy) = (self.clone(), self.clone(), self.clone());
+
+    vec![
+      ActionEntry::new(
+        "Show in file manager",
+        &self.description,
+        "system-file-manager",
+        move |_| show_in_file_manager(&show.path),
+      ),
+      ActionEntry::new(
+        "Open containing folder",
+        &self.description,
+        "folder",
+        move |_| folder.open_folder(),
+      ),
+      ActionEntry::new(
+        "Copy path",
+        self.path.display().to_string(),
+        "edit-copy",
+        move |_| copy.copy_path(),
+      ),
+    ]
+  }
+}
@@ -1,69 +1,206 @@
-use gtk::gdk_pixbuf::Pixbuf;
-
-use crate::{
-  extension::{config::ExtensionConfig, ExtensionContext},
-  launcher::{
-    util::{config::Config, icon::default_pixbuf},
-    window::Window,
-  },
+use gtk::{gdk::ModifierType, gdk_pixbuf::Pixbuf};
+
+use crate::launcher::{
+  preview::Preview,
+  util::{config::Config, icon::default_pixbuf},
+  window::Window,
 };
 
+pub mod action_entry;
 pub mod app_entry;
+pub mod calculator_entry;
+pub mod character_entry;
+pub mod clipboard_entry;
 pub mod extension_entry;
+pub mod file_entry;
+pub mod open_with_entry;
 pub mod script_entry;
+pub mod session_entry;
+pub mod shell_entry;
+pub mod web_entry;
+pub mod window_entry;
 
 #[derive(Debug, Clone)]
 pub enum ResultEntry {
+  Action(action_entry::ActionEntry),
   App(app_entry::AppEntry),
+  Calculator(calculator_entry::CalculatorEntry),
+  Character(character_entry::CharacterEntry),
+  Clipboard(clipboard_entry::ClipboardEntry),
   Extension(extension_entry::ExtensionEntry),
+  File(file_entry::FileEntry),
+  OpenWith(open_with_entry::OpenWithEntry),
   Script(script_entry::ScriptEntry),
+  Session(session_entry::SessionEntry),
+  Shell(shell_entry::ShellEntry),
+  Web(web_entry::WebEntry),
+  Window(window_entry::WindowEntry),
   None,
 }
 
 impl ResultEntry {
   pub fn name(&self) -> &str {
     match self {
+      ResultEntry::Action(action) => action.name(),
       ResultEntry::App(app) => &app.name,
+      ResultEntry::Calculator(calculator) => calculator.name(),
+      ResultEntry::Character(character) => character.name(),
+      ResultEntry::Clipboard(clipboard) => clipboard.name(),
       ResultEntry::Extension(ext) => &ext.name,
+      ResultEntry::File(file) => file.name(),
+      ResultEntry::OpenWith(open_with) => open_with.name(),
       ResultEntry::Script(script) => script.name(),
+      ResultEntry::Session(session) => session.name(),
+      ResultEntry::Shell(shell) => shell.name(),
+      ResultEntry::Web(web) => web.name(),
+      ResultEntry::Window(window) => window.name(),
       ResultEntry::None => "No results",
     }
   }
 
   pub fn description(&self) -> &str {
     match self {
+      ResultEntry::Action(action) => action.description(),
       ResultEntry::App(app) => &app.description,
+      ResultEntry::Calculator(calculator) => calculator.description(),
+      ResultEntry::Character(character) => character.description(),
+      ResultEntry::Clipboard(clipboard) => clipboard.description(),
       ResultEntry::Extension(ext) => &ext.description,
+      ResultEntry::File(file) => file.description(),
+      ResultEntry::OpenWith(open_with) => open_with.description(),
       ResultEntry::Script(script) => script.desc(),
+      ResultEntry::Session(session) => session.description(),
+      ResultEntry::Shell(shell) => shell.description(),
+      ResultEntry::Web(web) => web.description(),
+      ResultEntry::Window(window) => window.description(),
       ResultEntry::None => "No results found.",
     }
   }
 
-  pub fn icon(&self) -> Pixbuf {
+  pub fn icon(&self, size: i32) -> Pixbuf {
     match self {
-      ResultEntry::App(app) => app.icon(),
-      ResultEntry::Extension(ext) => ext.icon(),
-      ResultEntry::Script(script) => script.icon(),
-      ResultEntry::None => default_pixbuf(40),
+      ResultEntry::Action(action) => action.icon(size),
+      ResultEntry::App(app) => app.icon(size),
+      ResultEntry::Calculator(calculator) => calculator.icon(size),
+      ResultEntry::Character(character) => character.icon(size),
+      ResultEntry::Clipboard(clipboard) => clipboard.icon(size),
+      ResultEntry::Extension(ext) => ext.icon(size),
+      ResultEntry::File(file) => file.icon(size),
+      ResultEntry::OpenWith(open_with) => open_with.icon(size),
+      ResultEntry::Script(script) => script.icon(size),
+      ResultEntry::Session(session) => session.icon(size),
+      ResultEntry::Shell(shell) => shell.icon(size),
+      ResultEntry::Web(web) => web.icon(size),
+      ResultEntry::Window(window) => window.icon(size),
+      ResultEntry::None => default_pixbuf(size),
     }
   }
 
   pub fn execute(&self, window: Window) {
+    self.execute_with(window, ModifierType::empty());
+  }
+
+  /// Execute the entry with the modifier keys that were held while activating it, some entries
+  /// have secondary actions bound to modifiers (e.g. files open their containing folder with Shift).
+  pub fn execute_with(&self, window: Window, modifiers: ModifierType) {
     match self {
+      ResultEntry::Action(action) => action.execute(window),
       ResultEntry::App(app) => app.execute(window),
-      ResultEntry::Extension(ext) => {
-        if let Some(on_enter) = ext.on_enter.as_ref() {
-          let config = Config::read();
-          on_enter(ExtensionContext {
-            name: ext.extension_name.clone(),
-            window,
-            input: None,
-            config: ExtensionConfig::new(&config, &ext.extension_name),
-          })
-        }
-      }
+      ResultEntry::Calculator(calculator) => calculator.execute(),
+      ResultEntry::Character(character) => character.execute(window),
+      ResultEntry::Clipboard(clipboard) => clipboard.execute(modifiers),
+      ResultEntry::Extension(ext) => ext.run(&ext.on_enter, window),
+      ResultEntry::File(file) => file.execute(modifiers),
+      ResultEntry::OpenWith(open_with) => open_with.execute(window),
       ResultEntry::Script(script) => script.run(),
+      ResultEntry::Session(session) => session.execute(window),
+      ResultEntry::Shell(shell) => shell.execute(window, modifiers),
+      ResultEntry::Web(web) => web.execute(),
+      ResultEntry::Window(window) => window.execute(),
       ResultEntry::None => (),
     }
   }
+
+  /// The kind of result, or the extension it comes from, shown as a badge on its row.
+  pub fn badge(&self) -> Option<&str> {
+    match self {
+      ResultEntry::App(_) => Some("App"),
+      ResultEntry::Calculator(_) => Some("Calculator"),
+      ResultEntry::Character(_) => Some("Character"),
+      ResultEntry::Clipboard(_) => Some("Clipboard"),
+      ResultEntry::Extension(ext) => Some(ext.badge.as_deref().unwrap_or(&ext.extension_name)),
+      ResultEntry::File(file) if file.path.is_dir() => Some("Folder"),
+      ResultEntry::File(_) => Some("File"),
+      ResultEntry::Script(_) => Some("Script"),
+      ResultEntry::Session(_) => Some("Session"),
+      ResultEntry::Shell(_) => Some("Shell"),
+      ResultEntry::Web(_) => Some("Web"),
+      ResultEntry::Window(_) => Some("Window"),
+      ResultEntry::Action(_) | ResultEntry::OpenWith(_) | ResultEntry::None => None,
+    }
+  }
+
+  /// Shown on the right of the row while it's selected, e.g. `Enter to copy`.
+  pub fn hint(&self) -> Option<String> {
+    match self {
+      ResultEntry::Extension(ext) if ext.hint.is_some() => ext.hint.clone(),
+      ResultEntry::Action(_) | ResultEntry::None => None,
+      _ => Some(format!("Enter to {}", self.default_action().to_lowercase())),
+    }
+  }
+
+  /// Shown next to the results while the entry is selected.
+  pub fn preview(&self) -> Option<Preview> {
+    match self {
+      ResultEntry::Character(character) => character.preview(),
+      ResultEntry::Clipboard(clipboard) => clipboard.preview(),
+      ResultEntry::Extension(ext) => ext.preview.clone(),
+      ResultEntry::File(file) => file.preview(),
+      _ => None,
+    }
+  }
+
+  /// What pressing Enter on the entry does, the first item of its action menu.
+  fn default_action(&self) -> &str {
+    match self {
+      ResultEntry::App(_) => "Launch",
+      ResultEntry::Calculator(_) | ResultEntry::Character(_) => "Copy",
+      ResultEntry::Clipboard(clipboard) => clipboard.default_action(),
+      ResultEntry::File(_) | ResultEntry::OpenWith(_) | ResultEntry::Web(_) => "Open",
+      ResultEntry::Script(_) | ResultEntry::Shell(_) => "Run",
+      ResultEntry::Window(_) => "Switch to window",
+      ResultEntry::Session(session) => session.name(),
+      ResultEntry::Action(_) | ResultEntry::Extension(_) | ResultEntry::None => "Select",
+    }
+  }
+
+  /// The actions listed in the action menu (Alt+Enter or Tab), the first one is the same as
+  /// pressing Enter.
+  pub fn actions(&self, config: &Config) -> Vec<action_entry::ActionEntry> {
+    if matches!(self, ResultEntry::Action(_) | ResultEntry::None) {
+      return vec![];
+    }
+
+    let entry = self.clone();
+    let icon = self.clone();
+    let mut actions = vec![action_entry::ActionEntry::with_icon(
+      self.default_action(),
+      self.name(),
+      move |size| icon.icon(size),
+      move |window| entry.execute(window),
+    )];
+
+    actions.extend(match self {
+      ResultEntry::App(app) => app.actions(config),
+      ResultEntry::Character(character) => character.actions(),
+      ResultEntry::Clipboard(clipboard) => clipboard.actions(),
+      ResultEntry::Extension(ext) => ext.actions(),
+      ResultEntry::File(file) => file.actions(),
+      ResultEntry::Shell(shell) => shell.actions(config),
+      ResultEntry::Web(web) => web.actions(),
+      _ => vec![],
+    });
+
+    actions
+  }
 }
@@ -0,0 +1,119 @@
+use std::path::PathBuf;
+
+use gtk::{gdk_pixbuf::Pixbuf, gio::File, prelude::*};
+
+use crate::{entry::app_entry::AppEntry, launcher::window::Window};
+
+/// Something that can be passed to an app, parsed from the argument in an `app argument` query.
+#[derive(Debug, Clone)]
+pub enum OpenTarget {
+  File(PathBuf),
+  Uri(String),
+}
+
+impl OpenTarget {
+  /// Parse a query argument into a file or uri. Paths are expanded (`~`) and resolved relative to
+  /// the home directory, anything that looks like a domain (`example.com`) is opened as https.
+  pub fn parse(argument: &str) -> Option<Self> {
+    let argument = argument.trim();
+    if argument.is_empty() {
+      return None;
+    }
+
+    let home = PathBuf::from(std::env::var("HOME").expect("you are homeless"));
+    let path = if argument == "~" {
+      home
+    } else if let Some(rest) = argument.strip_prefix("~/") {
+      home.join(rest)
+    } else {
+      home.join(argument)
+    };
+
+    if path.exists() {
+      return Some(OpenTarget::File(path));
+    }
+
+    if let Some((scheme, rest)) = argument.split_once("://") {
+      let valid_scheme = scheme
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+      if valid_scheme && !scheme.is_empty() && !rest.is_empty() {
+        return Some(OpenTarget::Uri(argument.to_string()));
+      }
+    }
+
+    let host = argument.split('/').next().unwrap_or(argument);
+    let looks_like_domain = !argument.contains(char::is_whitespace)
+      && host.contains('.')
+      && host
+        .rsplit('.')
+        .next()
+        .map(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()))
+        .unwrap_or(false);
+
+    if looks_like_domain {
+      Some(OpenTarget::Uri(format!("https://{}", argument)))
+    } else {
+      None
+    }
+  }
+
+  /// The uri passed to the app's field codes.
+  pub fn uri(&self) -> String {
+    match self {
+      OpenTarget::File(path) => File::for_path(path).uri().to_string(),
+      OpenTarget::Uri(uri) => uri.clone(),
+    }
+  }
+
+  /// Whether `app` accepts this target through its `%f`/`%u` field codes.
+  pub fn supported_by(&self, app: &AppEntry) -> bool {
+    match self {
+      OpenTarget::File(_) => app.info.supports_files() || app.info.supports_uris(),
+      OpenTarget::Uri(_) => app.info.supports_uris(),
+    }
+  }
+
+  pub fn display(&self) -> String {
+    match self {
+      OpenTarget::File(path) => path.display().to_string(),
+      OpenTarget::Uri(uri) => uri.clone(),
+    }
+  }
+}
+
+/// An app with an argument, shown when the query is `app argument`.
+#[derive(Debug, Clone)]
+pub struct OpenWithEntry {
+  pub app: AppEntry,
+  pub target: OpenTarget,
+  description: String,
+}
+
+impl OpenWithEntry {
+  pub fn new(app: AppEntry, target: OpenTarget) -> Self {
+    let description = format!("Open {}", target.display());
+
+    Self {
+      app,
+      target,
+      description,
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.app.name
+  }
+
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    self.app.icon(size)
+  }
+
+  pub fn execute(&self, window: Window) {
+    self.app.execute_with(window, &[self.target.uri()]);
+  }
+}
@@ -1,10 +1,7 @@
-use gtk::{
-  gdk_pixbuf::{Pixbuf, PixbufLoader},
-  prelude::*,
-};
+use gtk::gdk_pixbuf::Pixbuf;
 
 use crate::{
-  launcher::util::icon::load_icon,
+  launcher::util::icon::{load_icon, load_svg},
   script::{Script, ScriptIcon},
 };
 
@@ -30,16 +27,10 @@ impl ScriptEntry {
     self.script.run();
   }
 
-  pub fn icon(&self) -> Pixbuf {
+  pub fn icon(&self, size: i32) -> Pixbuf {
     match &self.script.meta.icon {
-      ScriptIcon::Themed(value) => load_icon(&value, 40),
-      ScriptIcon::Svg(value) => {
-        let loader = PixbufLoader::new();
-        loader.write(value.as_bytes()).unwrap();
-        loader.close().unwrap();
-
-        loader.pixbuf().unwrap()
-      }
+      ScriptIcon::Themed(value) => load_icon(&value, size),
+      ScriptIcon::Svg(value) => load_svg(value, size),
     }
   }
 }
@@ -0,0 +1,81 @@
+use std::sync::atomic::Ordering;
+
+use gtk::{
+  gdk_pixbuf::Pixbuf, prelude::*, ButtonsType, DialogFlags, MessageDialog, MessageType,
+  ResponseType,
+};
+
+use crate::{
+  launcher::{
+    util::{config::ConfigSession, icon::load_icon},
+    window::Window,
+  },
+  provider::session::SessionAction,
+};
+
+#[derive(Debug, Clone)]
+pub struct SessionEntry {
+  pub action: SessionAction,
+  pub options: ConfigSession,
+}
+
+impl SessionEntry {
+  pub fn new(action: SessionAction, options: ConfigSession) -> Self {
+    Self { action, options }
+  }
+
+  pub fn name(&self) -> &str {
+    self.action.name()
+  }
+
+  pub fn description(&self) -> &str {
+    self.action.description()
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    load_icon(self.action.icon(), size)
+  }
+
+  /// Run the action, logging out, rebooting and shutting down ask for confirmation first unless
+  /// `confirm` is disabled.
+  pub fn execute(&self, window: Window) {
+    if self.action.is_destructive() && self.options.confirm.unwrap_or(true) {
+      self.confirm(window);
+    } else {
+      self.action.run(&self.options);
+    }
+  }
+
+  /// Ask for confirmation and run the action once it's given. The dialog isn't run in a nested
+  /// main loop, so the launcher keeps handling events while it is open. Without the daemon the
+  /// launcher exits once it's answered.
+  fn confirm(&self, window: Window) {
+    let dialog = MessageDialog::new(
+      None::<&gtk::Window>,
+      DialogFlags::MODAL,
+      MessageType::Question,
+      ButtonsType::None,
+      &format!("{}?", self.action.name()),
+    );
+    dialog.set_secondary_text(Some("Unsaved work in open applications may be lost."));
+    dialog.add_button("Cancel", ResponseType::Cancel);
+    dialog.add_button(self.action.name(), ResponseType::Accept);
+    dialog.set_default_response(ResponseType::Cancel);
+    dialog.set_keep_above(true);
+
+    window.state.dialog_open.store(true, Ordering::SeqCst);
+    let entry = self.clone();
+    dialog.connect_response(move |dialog, response| {
+      dialog.close();
+      window.state.dialog_open.store(false, Ordering::SeqCst);
+      if response == ResponseType::Accept {
+        entry.action.run(&entry.options);
+      }
+
+      if !window.config.main.daemon {
+        window.quit();
+      }
+    });
+    dialog.show_all();
+  }
+}
@@ -0,0 +1,83 @@
+use gtk::{gdk::ModifierType, gdk_pixbuf::Pixbuf};
+use log::{debug, error};
+
+use crate::{
+  entry::action_entry::ActionEntry,
+  launcher::{
+    util::{config::Config, icon::load_icon},
+    window::Window,
+  },
+  util::launch_detached,
+};
+
+#[derive(Debug, Clone)]
+pub struct ShellEntry {
+  pub command: String,
+  pub shell: String,
+}
+
+impl ShellEntry {
+  pub fn new(command: impl Into<String>, shell: impl Into<String>) -> Self {
+    Self {
+      command: command.into(),
+      shell: shell.into(),
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.command
+  }
+
+  pub fn description(&self) -> &str {
+    "Run command · Shift+Enter to run in a terminal"
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    load_icon("utilities-terminal", size)
+  }
+
+  /// Run the command in the background, holding Shift runs it through `terminal_command` instead
+  /// and keeps the terminal open afterwards.
+  pub fn execute(&self, window: Window, modifiers: ModifierType) {
+    if modifiers.contains(ModifierType::SHIFT_MASK) {
+      let terminal = match window.config.launcher.terminal_command.as_ref() {
+        Some(terminal) => terminal,
+        None => {
+          error!("terminal_command has to be set to run commands in a terminal");
+          return;
+        }
+      };
+
+      let full = shell_words::join([
+        &self.shell,
+        "-c",
+        &format!("{}; exec {}", self.command, self.shell),
+      ]);
+      let cmd = terminal.replace("{}", &full);
+      let spawn_args = shell_words::split(&cmd).unwrap();
+
+      debug!("Attempting to run {:?}", spawn_args);
+      launch_detached(spawn_args, vec![]);
+    } else {
+      debug!("Attempting to run {:?}", self.command);
+      launch_detached(vec![self.shell.as_str(), "-c", &self.command], vec![]);
+    }
+
+    window.state.shell.lock().unwrap().save_command(&self.command);
+  }
+
+  /// Secondary actions listed in the action menu.
+  pub fn actions(&self, config: &Config) -> Vec<ActionEntry> {
+    if config.launcher.terminal_command.is_none() {
+      return vec![];
+    }
+
+    let entry = self.clone();
+    vec![ActionEntry::new(
+      "Run in terminal",
+      &self.command,
+      "utilities-terminal",
+      move |window| entry.execute(window, ModifierType::SHIFT_MASK),
+    )]
+  }
+}
@@ -0,0 +1,65 @@
+use gtk::gdk_pixbuf::Pixbuf;
+use log::debug;
+
+use crate::{
+  entry::action_entry::ActionEntry,
+  launcher::util::{config::Quicklink, icon::load_icon},
+  provider::web::url_for,
+  util::{copy_to_clipboard, xdg_open},
+};
+
+#[derive(Debug, Clone)]
+pub struct WebEntry {
+  pub name: String,
+  pub url: String,
+  pub icon: Option<String>,
+}
+
+impl WebEntry {
+  pub fn new(name: impl Into<String>, url: impl Into<String>, icon: Option<String>) -> Self {
+    Self {
+      name: name.into(),
+      url: url.into(),
+      icon,
+    }
+  }
+
+  /// A quicklink with `argument` substituted into its url.
+  pub fn quicklink(link: &Quicklink, argument: &str) -> Self {
+    let name = if argument.is_empty() {
+      link.name.clone()
+    } else {
+      format!("{}: {}", link.name, argument)
+    };
+
+    Self::new(name, url_for(&link.url, argument), link.icon.clone())
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn description(&self) -> &str {
+    &self.url
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    load_icon(self.icon.as_deref().unwrap_or("web-browser"), size)
+  }
+
+  pub fn execute(&self) {
+    debug!("Opening {}", self.url);
+    xdg_open(vec![&self.url], vec![]);
+  }
+
+  /// Secondary actions listed in the action menu.
+  pub fn actions(&self) -> Vec<ActionEntry> {
+    let url = self.url.clone();
+    vec![ActionEntry::new(
+      "Copy URL",
+      &self.url,
+      "edit-copy",
+      move |_| copy_to_clipboard(&url),
+    )]
+  }
+}
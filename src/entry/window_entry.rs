@@ -0,0 +1,59 @@
+use gtk::gdk_pixbuf::Pixbuf;
+
+use crate::{
+  entry::app_entry::AppEntry,
+  launcher::util::icon::load_icon,
+  provider::windows::{WindowInfo, Windows},
+};
+
+#[derive(Debug, Clone)]
+pub struct WindowEntry {
+  pub window: WindowInfo,
+  /// Icon of the app the window belongs to.
+  pub icon: Option<String>,
+  description: String,
+}
+
+impl WindowEntry {
+  /// `apps` are used to find the app the window belongs to by its `StartupWMClass`.
+  pub fn new(window: WindowInfo, apps: &[AppEntry]) -> Self {
+    let app = apps.iter().find(|app| {
+      app
+        .startup_wm_class
+        .as_ref()
+        .map(|wm_class| window.has_class(wm_class))
+        .unwrap_or(false)
+    });
+
+    let description = match app {
+      Some(app) => format!("Switch to window · {}", app.name),
+      None => format!("Switch to window · {}", window.class),
+    };
+
+    Self {
+      icon: app.and_then(|app| app.icon.clone()),
+      window,
+      description,
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.window.title
+  }
+
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+
+  pub fn icon(&self, size: i32) -> Pixbuf {
+    match &self.icon {
+      Some(icon) => load_icon(icon, size),
+      // many apps use their WM_CLASS as icon name
+      None => load_icon(&self.window.class.to_lowercase(), size),
+    }
+  }
+
+  pub fn execute(&self) {
+    Windows::activate(&self.window);
+  }
+}
@@ -4,7 +4,7 @@ use crate::{
   entry::{extension_entry::ExtensionEntry, ResultEntry},
   extension::ExtensionContext,
   fuzzy::MatchingBlocks,
-  launcher::{result::ResultWidget, window::Window},
+  launcher::{preview::Preview, result::ResultWidget, window::Window},
   util::no_match,
 };
 
@@ -23,6 +23,22 @@ pub struct ExtensionResponseLine {
   pub icon: ExtensionResponseIcon,
   pub match_: MatchingBlocks,
   pub on_enter: OnEnterFn,
+  /// Alternate actions listed in the action menu (Alt+Enter or Tab) after `on_enter`.
+  pub actions: Vec<ExtensionResponseAction>,
+  /// Shown on the right of the row when it's selected instead of `Enter to select`.
+  pub hint: Option<String>,
+  /// Shown on the right of the row instead of the extension's name.
+  pub badge: Option<String>,
+  /// Shown next to the results while the line is selected.
+  pub preview: Option<Preview>,
+}
+
+/// An alternate action of a line, e.g. "Copy as HTML" next to "Copy".
+pub struct ExtensionResponseAction {
+  pub name: String,
+  pub description: String,
+  pub icon: ExtensionResponseIcon,
+  pub on_enter: OnEnterFn,
 }
 
 #[derive(Debug, Clone)]
@@ -62,6 +78,10 @@ impl ExtensionResponse {
       icon,
       match_: (vec![], 0),
       on_enter: Rc::new(None),
+      actions: vec![],
+      hint: None,
+      badge: None,
+      preview: None,
     });
 
     self
@@ -82,6 +102,10 @@ impl ExtensionResponse {
       icon,
       match_,
       on_enter: Rc::new(None),
+      actions: vec![],
+      hint: None,
+      badge: None,
+      preview: None,
     });
 
     self
@@ -103,6 +127,10 @@ impl ExtensionResponse {
       icon,
       match_: no_match(),
       on_enter: Rc::new(Some(Box::new(on_enter))),
+      actions: vec![],
+      hint: None,
+      badge: None,
+      preview: None,
     });
 
     self
@@ -125,11 +153,74 @@ impl ExtensionResponse {
       icon,
       match_,
       on_enter: Rc::new(Some(Box::new(on_enter))),
+      actions: vec![],
+      hint: None,
+      badge: None,
+      preview: None,
     });
 
     self
   }
 
+  /// Add an alternate action to the last line, it's listed in the line's action menu.
+  ///
+  /// ```rust,ignore
+  /// response
+  ///   .line_on_enter("Zero Width Space", "Copy", icon.clone(), |_| copy_to_clipboard("\u{200B}"))
+  ///   .action("Copy as HTML entity", "&#8203;", icon, |_| copy_to_clipboard("&#8203;"));
+  /// ```
+  pub fn action<F>(
+    &mut self,
+    name: impl Into<String>,
+    description: impl Into<String>,
+    icon: ExtensionResponseIcon,
+    on_enter: F,
+  ) -> &mut Self
+  where
+    F: Fn(ExtensionContext) + 'static,
+  {
+    if let Some(line) = self.lines.last_mut() {
+      line.action(name, description, icon, on_enter);
+    }
+
+    self
+  }
+
+  /// Set the hint of the last line, shown on the right while it's selected.
+  ///
+  /// ```rust,ignore
+  /// response
+  ///   .line_on_enter("Zero Width Space", "", icon, |_| copy_to_clipboard("\u{200B}"))
+  ///   .hint("Enter to copy")
+  ///   .badge("Unicode")
+  ///   .preview(Preview::Text("U+200B ZERO WIDTH SPACE".into()));
+  /// ```
+  pub fn hint(&mut self, hint: impl Into<String>) -> &mut Self {
+    if let Some(line) = self.lines.last_mut() {
+      line.hint(hint);
+    }
+
+    self
+  }
+
+  /// Set the badge of the last line.
+  pub fn badge(&mut self, badge: impl Into<String>) -> &mut Self {
+    if let Some(line) = self.lines.last_mut() {
+      line.badge(badge);
+    }
+
+    self
+  }
+
+  /// Set the preview of the last line.
+  pub fn preview(&mut self, preview: Preview) -> &mut Self {
+    if let Some(line) = self.lines.last_mut() {
+      line.preview(preview);
+    }
+
+    self
+  }
+
   pub fn build(&self, window: Window) -> Vec<ResultWidget> {
     let mut result = Vec::new();
 
@@ -159,6 +250,10 @@ impl ExtensionResponseLine {
       match_: no_match(),
       icon: ExtensionResponseIcon::themed(""),
       on_enter: Rc::new(None),
+      actions: vec![],
+      hint: None,
+      badge: None,
+      preview: None,
     }
   }
 
@@ -186,6 +281,41 @@ impl ExtensionResponseLine {
     self.on_enter = Rc::new(Some(Box::new(on_enter)));
     self
   }
+
+  /// Add an alternate action, listed in the line's action menu after `on_enter`.
+  pub fn action<F>(
+    &mut self,
+    name: impl Into<String>,
+    description: impl Into<String>,
+    icon: ExtensionResponseIcon,
+    on_enter: F,
+  ) -> &mut Self
+  where
+    F: Fn(ExtensionContext) + 'static,
+  {
+    self.actions.push(ExtensionResponseAction {
+      name: name.into(),
+      description: description.into(),
+      icon,
+      on_enter: Rc::new(Some(Box::new(on_enter))),
+    });
+    self
+  }
+
+  pub fn hint(&mut self, hint: impl Into<String>) -> &mut Self {
+    self.hint = Some(hint.into());
+    self
+  }
+
+  pub fn badge(&mut self, badge: impl Into<String>) -> &mut Self {
+    self.badge = Some(badge.into());
+    self
+  }
+
+  pub fn preview(&mut self, preview: Preview) -> &mut Self {
+    self.preview = Some(preview);
+    self
+  }
 }
 
 impl ExtensionResponseIcon {
@@ -211,6 +341,10 @@ impl fmt::Debug for ExtensionResponseLine {
       .field("description", &self.description)
       .field("icon", &self.icon)
       .field("match_", &self.match_)
+      .field("actions", &self.actions)
+      .field("hint", &self.hint)
+      .field("badge", &self.badge)
+      .field("preview", &self.preview)
       .finish()
   }
 }
@@ -223,6 +357,31 @@ impl Clone for ExtensionResponseLine {
       match_: self.match_.clone(),
       icon: self.icon.clone(),
       on_enter: self.on_enter.clone(),
+      actions: self.actions.clone(),
+      hint: self.hint.clone(),
+      badge: self.badge.clone(),
+      preview: self.preview.clone(),
+    }
+  }
+}
+
+impl fmt::Debug for ExtensionResponseAction {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ExtensionResponseAction")
+      .field("name", &self.name)
+      .field("description", &self.description)
+      .field("icon", &self.icon)
+      .finish()
+  }
+}
+
+impl Clone for ExtensionResponseAction {
+  fn clone(&self) -> Self {
+    Self {
+      name: self.name.clone(),
+      description: self.description.clone(),
+      icon: self.icon.clone(),
+      on_enter: self.on_enter.clone(),
     }
   }
 }
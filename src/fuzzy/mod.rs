@@ -76,14 +76,22 @@ pub fn get_matching_blocks(a: &str, b: &str) -> MatchingBlocks {
 }
 
 pub fn get_score(a: &str, b: &str) -> usize {
+  get_score_and_blocks(a, b).0
+}
+
+/// The score of `a` against `b` together with the blocks it was computed from, so callers that
+/// highlight the match don't compute the blocks a second time.
+pub fn get_score_and_blocks(a: &str, b: &str) -> (usize, MatchingBlocks) {
   let a_len = a.chars().count();
   let b_len = b.chars().count();
   let max_len = max(a_len, b_len);
-  let (blocks, matching_cars) = get_matching_blocks(a, b);
+  let matching_blocks = get_matching_blocks(a, b);
+  let (blocks, matching_cars) = &matching_blocks;
 
-  let mut base_similarity = (matching_cars as f64) / (a_len as f64);
+  let mut base_similarity = (*matching_cars as f64) / (a_len as f64);
 
   for (index, _) in blocks {
+    let index = *index;
     let is_word_boundary = index == 0 || slice_utf8(b, index - 1, index) == " ";
     if !is_word_boundary {
       base_similarity -= 0.5 / a_len as f64;
@@ -93,5 +101,5 @@ pub fn get_score(a: &str, b: &str) -> usize {
   let score =
     100.0 * base_similarity * a_len as f64 / (a_len as f64 + (max_len - a_len) as f64 * 0.001);
 
-  score.round() as usize
+  (score.round() as usize, matching_blocks)
 }
@@ -1,5 +1,7 @@
 // Navigation helper
 pub mod navigation;
+// Preview pane of the selected result
+pub mod preview;
 // Result UI element
 pub mod result;
 // Launcher utilties
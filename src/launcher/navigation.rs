@@ -4,11 +4,16 @@ use crate::launcher::result::ResultWidget;
 
 use super::util::query_history::QueryHistory;
 
+/// Number of results skipped by `page_up` and `page_down`.
+const PAGE_SIZE: u16 = 5;
+
 #[derive(Debug, Clone)]
 pub struct Navigation {
   pub results: Vec<ResultWidget>,
   pub query_history: Arc<QueryHistory>,
   pub selected: Option<u16>,
+  /// The results and selection the action menu was opened from, `None` when it isn't open.
+  pub parent: Option<(Vec<ResultWidget>, u16)>,
 }
 
 impl Navigation {
@@ -16,6 +21,7 @@ impl Navigation {
     Self {
       results: vec![],
       selected: None,
+      parent: None,
       query_history,
     }
   }
@@ -37,7 +43,7 @@ impl Navigation {
 
   pub fn set_indicies(&mut self) {
     for (i, result) in self.results.iter_mut().enumerate() {
-      result.index = i as u16;
+      result.set_index(i as u16);
     }
   }
 
@@ -82,4 +88,44 @@ impl Navigation {
       }
     }
   }
+
+  pub fn page_up(&mut self) {
+    if let Some(selected) = self.selected {
+      self.select(selected.saturating_sub(PAGE_SIZE));
+    }
+  }
+
+  pub fn page_down(&mut self) {
+    if let Some(selected) = self.selected {
+      let last = self.results.len() as u16 - 1;
+      self.select((selected + PAGE_SIZE).min(last));
+    }
+  }
+
+  /// Like `go_up` without wrapping around, one scroll step shouldn't jump to the last result.
+  pub fn scroll_up(&mut self) {
+    if let Some(selected) = self.selected {
+      if selected > 0 {
+        self.select(selected - 1);
+      }
+    }
+  }
+
+  pub fn scroll_down(&mut self) {
+    if let Some(selected) = self.selected {
+      if selected + 1 < self.results.len() as u16 {
+        self.select(selected + 1);
+      }
+    }
+  }
+
+  pub fn first(&mut self) {
+    self.select(0);
+  }
+
+  pub fn last(&mut self) {
+    if !self.results.is_empty() {
+      self.select(self.results.len() as u16 - 1);
+    }
+  }
 }
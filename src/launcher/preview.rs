@@ -0,0 +1,224 @@
+use std::{
+  cell::RefCell,
+  fs::File,
+  io::Read,
+  path::{Path, PathBuf},
+};
+
+use gtk::{
+  gdk_pixbuf::{InterpType, Pixbuf},
+  gio::{
+    self, content_type_get_description, content_type_get_icon, content_type_guess,
+    content_type_is_a, Cancellable,
+  },
+  glib::{self, format_size, MainContext, PRIORITY_DEFAULT},
+  prelude::*,
+  Builder, Image, Label,
+};
+
+use crate::launcher::util::icon::load_gicon;
+
+/// Width and height the previewed images are scaled down to.
+const IMAGE_SIZE: i32 = 280;
+/// Size of the icon shown for files that can't be previewed.
+const ICON_SIZE: i32 = 96;
+/// Only the start of text files is read, and only this many lines of any text are shown.
+const TEXT_BYTES: u64 = 8192;
+const TEXT_LINES: usize = 20;
+/// Entries of a folder that are listed.
+const FOLDER_ENTRIES: usize = 20;
+/// Entries of a folder that are read at most, bigger folders show this many items with a `+`.
+const FOLDER_READ: usize = 1000;
+
+thread_local! {
+  /// Loading of the previewed file, cancelled when another preview replaces it.
+  static LOADING: RefCell<Option<Cancellable>> = const { RefCell::new(None) };
+}
+
+/// Content of the preview pane next to the results, shown for the selected result when it has
+/// one.
+#[derive(Debug, Clone)]
+pub enum Preview {
+  /// Text, e.g. a clipboard item or the result of a calculation.
+  Text(String),
+  /// An image, scaled down to fit the pane.
+  Image(Pixbuf),
+  /// A file or folder. Images are shown, text files show their first lines, folders their entries
+  /// and other files their icon, all with the type and size.
+  File(PathBuf),
+}
+
+impl Preview {
+  /// Fill the preview pane of the window's builder, parts the preview doesn't have are hidden.
+  /// Files are read and decoded in the background, their icon is shown until they're loaded.
+  pub fn render(&self, builder: &Builder) {
+    let cancellable = Cancellable::new();
+    if let Some(previous) = LOADING.with(|loading| loading.replace(Some(cancellable.clone()))) {
+      previous.cancel();
+    }
+
+    match self {
+      Preview::Text(content) => show(builder, None, Some(&first_lines(content)), None),
+      Preview::Image(pixbuf) => show(
+        builder,
+        Some(&fit(pixbuf)),
+        None,
+        Some(&format!("{}×{}", pixbuf.width(), pixbuf.height())),
+      ),
+      Preview::File(path) => file_preview(builder, path, &cancellable),
+    }
+  }
+}
+
+fn show(builder: &Builder, pixbuf: Option<&Pixbuf>, content: Option<&str>, details: Option<&str>) {
+  let image: Image = builder.object("preview-image").unwrap();
+  let text: Label = builder.object("preview-text").unwrap();
+  let info: Label = builder.object("preview-info").unwrap();
+
+  image.set_from_pixbuf(pixbuf);
+  image.set_visible(pixbuf.is_some());
+  text.set_text(content.unwrap_or_default());
+  text.set_visible(content.is_some());
+  info.set_text(details.unwrap_or_default());
+  info.set_visible(details.is_some());
+}
+
+fn file_preview(builder: &Builder, path: &Path, cancellable: &Cancellable) {
+  let metadata = match path.metadata() {
+    Ok(metadata) => metadata,
+    Err(err) => {
+      let details = format!("Can't read {}: {}", path.display(), err);
+      return show(builder, None, None, Some(&details));
+    }
+  };
+
+  if metadata.is_dir() {
+    let icon = load_gicon(&content_type_get_icon("inode/directory"), ICON_SIZE);
+    show(builder, Some(&icon), None, Some("Folder"));
+
+    let (builder, path) = (builder.clone(), path.to_path_buf());
+    return in_background(
+      cancellable,
+      move || read_folder(&path),
+      move |(entries, details)| show(&builder, Some(&icon), Some(&entries), Some(&details)),
+    );
+  }
+
+  let content_type = content_type_guess(Some(path), &[]).0;
+  let details = format!(
+    "{} · {}",
+    content_type_get_description(&content_type),
+    format_size(metadata.len())
+  );
+  let icon = load_gicon(&content_type_get_icon(&content_type), ICON_SIZE);
+  show(builder, Some(&icon), None, Some(&details));
+
+  if content_type.starts_with("image/") {
+    let (builder, decoding) = (builder.clone(), cancellable.clone());
+    gio::File::for_path(path).read_async(PRIORITY_DEFAULT, Some(cancellable), move |stream| {
+      if let Ok(stream) = stream {
+        Pixbuf::from_stream_at_scale_async(
+          &stream,
+          IMAGE_SIZE,
+          IMAGE_SIZE,
+          true,
+          Some(&decoding),
+          move |pixbuf| {
+            if let Ok(pixbuf) = pixbuf {
+              show(&builder, Some(&pixbuf), None, Some(&details));
+            }
+          },
+        );
+      }
+    });
+  } else if content_type_is_a(&content_type, "text/plain") {
+    let (builder, path) = (builder.clone(), path.to_path_buf());
+    in_background(
+      cancellable,
+      move || {
+        let mut head = vec![];
+        File::open(path)
+          .and_then(|file| file.take(TEXT_BYTES).read_to_end(&mut head))
+          .ok()
+          .map(|_| first_lines(&String::from_utf8_lossy(&head)))
+      },
+      move |text| {
+        if let Some(text) = text {
+          show(&builder, None, Some(&text), Some(&details));
+        }
+      },
+    );
+  }
+}
+
+/// The first entries of a folder by name along with the number of entries, at most `FOLDER_READ`
+/// entries are read so huge folders don't take long.
+fn read_folder(path: &Path) -> (String, String) {
+  let mut entries = path
+    .read_dir()
+    .map(|entries| {
+      entries
+        .flatten()
+        .take(FOLDER_READ)
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<String>>()
+    })
+    .unwrap_or_default();
+  entries.sort();
+
+  let details = if entries.len() == FOLDER_READ {
+    format!("Folder · {}+ items", FOLDER_READ)
+  } else {
+    format!("Folder · {} items", entries.len())
+  };
+  entries.truncate(FOLDER_ENTRIES);
+
+  (entries.join("\n"), details)
+}
+
+/// Run `load` on another thread and pass its result to `done` on the main thread, unless the
+/// preview was replaced in the meantime.
+fn in_background<T, L, D>(cancellable: &Cancellable, load: L, done: D)
+where
+  T: Send + 'static,
+  L: FnOnce() -> T + Send + 'static,
+  D: FnOnce(T) + 'static,
+{
+  let (sender, receiver) = MainContext::channel(PRIORITY_DEFAULT);
+  std::thread::spawn(move || {
+    // the receiver is gone when the window was closed
+    let _ = sender.send(load());
+  });
+
+  let cancellable = cancellable.clone();
+  let mut done = Some(done);
+  receiver.attach(None, move |value| {
+    if let Some(done) = done.take().filter(|_| !cancellable.is_cancelled()) {
+      done(value);
+    }
+    glib::Continue(false)
+  });
+}
+
+fn first_lines(text: &str) -> String {
+  text
+    .lines()
+    .take(TEXT_LINES)
+    .collect::<Vec<&str>>()
+    .join("\n")
+}
+
+fn fit(pixbuf: &Pixbuf) -> Pixbuf {
+  let scale = IMAGE_SIZE as f64 / pixbuf.width().max(pixbuf.height()) as f64;
+  if scale >= 1.0 {
+    return pixbuf.clone();
+  }
+
+  let (width, height) = (
+    ((pixbuf.width() as f64 * scale) as i32).max(1),
+    ((pixbuf.height() as f64 * scale) as i32).max(1),
+  );
+  pixbuf
+    .scale_simple(width, height, InterpType::Bilinear)
+    .unwrap_or_else(|| pixbuf.clone())
+}
@@ -1,6 +1,35 @@
-use gtk::{prelude::*, Builder, EventBox, Image, Label};
+use std::{cell::Cell, rc::Rc};
 
-use crate::{entry::ResultEntry, fuzzy::{ MatchingBlocks, slice_utf8 }, launcher::window::Window};
+use gtk::{
+  gdk::{
+    EventMask, EventType, ModifierType, ScrollDirection, BUTTON_MIDDLE, BUTTON_PRIMARY,
+    BUTTON_SECONDARY,
+  },
+  glib::{idle_add_local_once, markup_escape_text},
+  prelude::*,
+  Builder, EventBox, Image, Label,
+};
+
+use crate::{
+  entry::ResultEntry,
+  fuzzy::{slice_utf8, MatchingBlocks},
+  launcher::{
+    util::{
+      config::{Density, ResultRows},
+      display::IconScale,
+      icon::set_scaled_pixbuf,
+      theme::MatchedTextHlColors,
+    },
+    window::Window,
+  },
+};
+
+/// Rows that are probably visible as soon as the results are shown, their icons are loaded right
+/// away instead of when they're first drawn so they don't flicker.
+const EAGER_ICONS: u16 = 12;
+/// Width of the name and description column when hints or badges are shown, narrower than the
+/// 410 in result.ui to leave room for them on its right. Long names are ellipsized to fit.
+const NAME_WIDTH_WITH_HINTS: i32 = 300;
 
 #[derive(Debug, Clone)]
 pub struct ResultWidget {
@@ -10,6 +39,13 @@ pub struct ResultWidget {
   pub entry: ResultEntry,
   pub index: u16,
   pub window: Window,
+  /// Colors and style of the matched characters in the name.
+  pub highlight: MatchedTextHlColors,
+  /// Whether the hint, badge and preview are shown.
+  pub rows: ResultRows,
+  /// Set once the icon is loaded, icons of rows that are never scrolled to aren't decoded at all.
+  icon_loaded: Rc<Cell<bool>>,
+  icon_scale: IconScale,
 }
 
 impl ResultWidget {
@@ -18,62 +54,178 @@ impl ResultWidget {
     let builder = Builder::new();
     builder.add_from_string(result_str).unwrap();
 
-    let item_name: Label = builder.object("item-name").unwrap();
     let item_icon: Image = builder.object("item-icon").unwrap();
     let item_desc: Label = builder.object("item-descr").unwrap();
+    let item_container: gtk::Box = builder.object("item-container").unwrap();
 
-    item_name.set_text(entry.name());
-
-    let open_tag = format!(
-      "<span foreground=\"{}\">",
-      window
-        .config
-        .theme()
-        .inner
-        .matched_text_hl_colors
-        .when_selected
-    );
-    let close_tag = "</span>";
+    let rows = window.config.results();
+    let density = rows.density;
+    let scale = window.icon_scale.get();
 
-    let name_c = match_.0
-        .iter()
-        .rev()
-        .fold(entry.name().to_string(), |name_c, (index, chars)| {
-            [slice_utf8(&name_c, 0, *index),
-            &open_tag,
-            &chars,
-            close_tag,
-            slice_utf8(&name_c, *index + chars.chars().count(), name_c.chars().count())].concat()
-    });
+    // the size is kept while the icon isn't loaded yet
+    let icon_size = scale.logical_size(density.icon_size());
+    item_icon.set_size_request(icon_size, icon_size);
+    item_icon.set_pixel_size(icon_size);
+    item_icon.set_margin(2);
+
+    let icon_loaded = Rc::new(Cell::new(false));
+    let (loaded, icon_entry) = (icon_loaded.clone(), entry.clone());
+    item_icon.connect_draw(move |item_icon, _| {
+      if !loaded.replace(true) {
+        // changing the image while it's drawn would draw it with the wrong size
+        let (item_icon, entry) = (item_icon.clone(), icon_entry.clone());
+        idle_add_local_once(move || {
+          let icon = entry.icon(scale.pixel_size(density.icon_size()));
+          set_scaled_pixbuf(&item_icon, &icon, scale.device);
+        });
+      }
 
-    item_name.set_markup(&name_c);
+      Inhibit(false)
+    });
 
-    item_icon.set_from_pixbuf(Some(&entry.icon()));
+    item_container.set_margin_top(density.padding());
+    item_container.set_margin_bottom(density.padding());
 
-    item_icon.set_pixel_size(40);
-    item_icon.set_margin(2);
     item_desc.set_text(entry.description());
+    // `show_all` on the results would show it again
+    item_desc.set_no_show_all(!density.show_description());
+    item_desc.set_visible(density.show_description());
+    if density == Density::Compact {
+      let item_frame: EventBox = builder.object("item-frame").unwrap();
+      item_frame.style_context().add_class("small-result-item");
+    }
+
+    if rows.hints || rows.badges {
+      let name_wrapper: gtk::Box = builder.object("name_wrapper").unwrap();
+      name_wrapper.set_width_request(NAME_WIDTH_WITH_HINTS);
+    }
+
+    let highlight = window.theme.theme().inner.matched_text_hl_colors;
+
+    if let Some(badge) = entry.badge().filter(|_| rows.badges) {
+      let item_badge: Label = builder.object("item-badge").unwrap();
+      item_badge.set_text(badge);
+      item_badge.show();
+    }
 
-    Self {
+    let result = Self {
       builder,
       selected: false,
       match_,
       entry,
       index: 0,
       window,
-    }
+      highlight,
+      rows,
+      icon_loaded,
+      icon_scale: scale,
+    };
+    result.render_name();
+
+    result
   }
 
   pub fn select(&mut self) {
     self.selected = true;
     let item_box: EventBox = self.builder.object("item-box").unwrap();
     item_box.style_context().add_class("selected");
+    self.render_name();
+    self.render_hint();
+
+    if self.rows.preview {
+      self.window.show_preview(self.entry.preview());
+    }
   }
 
   pub fn deselect(&mut self) {
     self.selected = false;
     let item_box: EventBox = self.builder.object("item-box").unwrap();
     item_box.style_context().remove_class("selected");
+    self.render_name();
+    self.render_hint();
+  }
+
+  /// Set the position of the result, the first nine can be opened with Alt+1 to Alt+9.
+  pub fn set_index(&mut self, index: u16) {
+    self.index = index;
+    self.render_hint();
+
+    if index < EAGER_ICONS {
+      self.load_icon();
+    }
+  }
+
+  /// Decode the icon and show it, unless it already is.
+  fn load_icon(&self) {
+    if self.icon_loaded.replace(true) {
+      return;
+    }
+
+    let item_icon: Image = self.builder.object("item-icon").unwrap();
+    let size = self.icon_scale.pixel_size(self.rows.density.icon_size());
+    set_scaled_pixbuf(&item_icon, &self.entry.icon(size), self.icon_scale.device);
+  }
+
+  /// Show what Enter does when the result is selected, and its Alt+number shortcut otherwise.
+  fn render_hint(&self) {
+    let item_shortcut: Label = self.builder.object("item-shortcut").unwrap();
+
+    let hint = if !self.rows.hints {
+      None
+    } else if self.selected {
+      self.entry.hint()
+    } else if self.index < 9 {
+      Some(format!("Alt+{}", self.index + 1))
+    } else {
+      None
+    };
+
+    item_shortcut.set_text(hint.as_deref().unwrap_or_default());
+    item_shortcut.set_visible(hint.is_some());
+  }
+
+  /// Set the name with the matched characters highlighted in the theme's color for the current
+  /// selection state.
+  fn render_name(&self) {
+    let item_name: Label = self.builder.object("item-name").unwrap();
+    let name = self.entry.name();
+
+    let color = if self.selected {
+      &self.highlight.when_selected
+    } else {
+      &self.highlight.when_not_selected
+    };
+    let mut open_tag = format!("<span foreground=\"{}\"", markup_escape_text(color));
+    if self.highlight.bold {
+      open_tag.push_str(" weight=\"bold\"");
+    }
+    if self.highlight.underline {
+      open_tag.push_str(" underline=\"single\"");
+    }
+    open_tag.push('>');
+    let close_tag = "</span>";
+
+    let mut blocks = self.match_.0.clone();
+    blocks.sort_by_key(|(index, _)| *index);
+
+    let length = name.chars().count();
+    let mut markup = String::new();
+    let mut position = 0;
+    for (index, chars) in blocks {
+      let end = (index + chars.chars().count()).min(length);
+      if index < position || index >= end {
+        continue;
+      }
+
+      markup.push_str(&markup_escape_text(slice_utf8(name, position, index)));
+      markup.push_str(&open_tag);
+      markup.push_str(&markup_escape_text(slice_utf8(name, index, end)));
+      markup.push_str(close_tag);
+      position = end;
+    }
+    markup.push_str(&markup_escape_text(slice_utf8(name, position, length)));
+
+    item_name.set_markup(&markup);
   }
 
   pub fn setup(&self) {
@@ -88,25 +240,86 @@ impl ResultWidget {
       Inhibit(false)
     });
 
+    // set on a single press on this row, a release without it (the second click of a double click,
+    // or a press that started somewhere else) doesn't activate anything
+    let pressed = Rc::new(Cell::new(false));
+
+    let result_press = self.clone();
+    let press = pressed.clone();
+    item_box.connect_button_press_event(move |_, e| {
+      press.set(e.event_type() == EventType::ButtonPress);
+      if e.event_type() == EventType::ButtonPress {
+        let mut navigation = result_press.window.navigation.lock().unwrap();
+        navigation.select(result_press.index);
+      }
+
+      Inhibit(false)
+    });
+
     let result_button = self.clone();
-    item_box.connect_button_release_event(move |_, _| {
-      let navigation = result_button.window.navigation.lock().unwrap();
-
-      if let Some(selected) = navigation.selected {
-        if result_button.window.config.main.daemon {
-          result_button.window.window.hide();
-          navigation.results[selected as usize]
-            .entry
-            .execute(result_button.window.clone());
-        } else {
-          navigation.results[selected as usize]
-            .entry
-            .execute(result_button.window.clone());
-          std::process::exit(0);
+    item_box.connect_button_release_event(move |_, e| {
+      if !pressed.replace(false) {
+        return Inhibit(false);
+      }
+
+      match e.button() {
+        BUTTON_SECONDARY => {
+          let closed = result_button.window.close_action_menu();
+          if !closed {
+            result_button.window.open_action_menu();
+          }
         }
+        BUTTON_MIDDLE => {
+          // the same alternate action as Shift+Enter
+          let entry = result_button.entry.clone();
+          result_button
+            .activate(move |window| entry.execute_with(window, ModifierType::SHIFT_MASK));
+        }
+        BUTTON_PRIMARY => {
+          let entry = result_button.entry.clone();
+          let state = e.state();
+          result_button.activate(move |window| entry.execute_with(window, state));
+        }
+        _ => {}
       }
 
-      Inhibit(false)
+      Inhibit(true)
     });
+
+    let result_scroll = self.clone();
+    item_box.add_events(EventMask::SCROLL_MASK | EventMask::SMOOTH_SCROLL_MASK);
+    item_box.connect_scroll_event(move |_, e| {
+      let down = match e.direction() {
+        ScrollDirection::Up => false,
+        ScrollDirection::Down => true,
+        ScrollDirection::Smooth => match e.delta() {
+          (_, y) if y < 0.0 => false,
+          (_, y) if y > 0.0 => true,
+          _ => return Inhibit(false),
+        },
+        _ => return Inhibit(false),
+      };
+
+      let mut navigation = result_scroll.window.navigation.lock().unwrap();
+      if down {
+        navigation.scroll_down();
+      } else {
+        navigation.scroll_up();
+      }
+
+      Inhibit(true)
+    });
+  }
+
+  /// Run `run` with the result's window, hiding it first in daemon mode and quitting afterwards
+  /// otherwise.
+  fn activate<F: FnOnce(Window)>(&self, run: F) {
+    if self.window.config.main.daemon {
+      self.window.window.hide();
+      run(self.window.clone());
+    } else {
+      run(self.window.clone());
+      self.window.quit();
+    }
   }
 }
@@ -8,14 +8,24 @@ use gtk::{
 use log::debug;
 use regex::Regex;
 
-use crate::{entry::app_entry::AppEntry, launcher::util::icon::load_icon};
+use crate::{
+  entry::app_entry::AppEntry,
+  launcher::util::config::Config,
+};
 
 pub struct App;
 
 impl App {
-  pub fn all() -> Vec<AppEntry> {
+  /// Every app that is shown in the results, `hidden` are the apps hidden through the action menu
+  /// since the launcher started, on top of `[apps] hidden` and `dlauncher.hidden`.
+  pub fn all(config: &Config, hidden: &[String]) -> Vec<AppEntry> {
     debug!("Reading apps");
     let mut results = Vec::new();
+    let overrides = config.apps();
+    let mut hidden = hidden.to_vec();
+    hidden.extend(overrides.hidden.unwrap_or_default());
+    hidden.extend(config.read_hidden_apps());
+    let custom = overrides.overrides.unwrap_or_default();
 
     let re = Regex::new(r"%[uUfFdDnNickvm]").unwrap();
 
@@ -24,37 +34,45 @@ impl App {
         continue;
       }
 
-      if let Some(exec) = a.commandline() {
-        let icon = if a.icon().is_none() {
-          None
-        } else {
-          let st = gtk::prelude::IconExt::to_string(&a.icon().unwrap())
-            .unwrap()
-            .to_string();
-
-          Some(load_icon(&st, 40))
-        };
+      // AppInfo::all only returns desktop entries on unix, entries whose `TryExec` isn't found have
+      // already been skipped by GIO.
+      let info = match a.downcast::<DesktopAppInfo>() {
+        Ok(info) => info,
+        Err(_) => continue,
+      };
 
-        if let Some(file) = a.id() {
-          let exec: Vec<String> =
-            shell_words::split(&*re.replace(&*exec.display().to_string(), "")).unwrap();
+      if let Some(exec) = info.commandline() {
+        if let Some(file) = info.id().filter(|id| !hidden.contains(&id.to_string())) {
+          let custom = custom.get(file.as_str()).cloned().unwrap_or_default();
 
-          let terminal = if let Some(desktop) = DesktopAppInfo::new(&a.id().unwrap()) {
-            desktop.boolean("Terminal")
-          } else {
-            false
+          let icon = match &custom.icon {
+            Some(icon) => Some(icon.clone()),
+            None => info
+              .icon()
+              .and_then(|icon| gtk::prelude::IconExt::to_string(&icon))
+              .map(|icon| icon.to_string()),
           };
 
+          // Field codes are only stripped for matching and terminal_command, launching goes
+          // through GIO which substitutes them properly.
+          let exec: Vec<String> =
+            shell_words::split(&re.replace(&exec.display().to_string(), "")).unwrap();
+
           results.push(AppEntry {
-            name: a.display_name().to_string(),
-            description: a
+            name: custom
+              .name
+              .unwrap_or_else(|| info.display_name().to_string()),
+            description: info
               .description()
               .unwrap_or_else(|| GString::from(""))
               .to_string(),
             file: PathBuf::from(file.to_string()),
             icon,
             exec,
-            terminal,
+            keywords: custom.keywords.unwrap_or_default(),
+            terminal: info.boolean("Terminal"),
+            startup_wm_class: info.startup_wm_class().map(|class| class.to_string()),
+            info,
           })
         }
       }
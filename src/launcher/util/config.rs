@@ -1,5 +1,7 @@
 use std::{
-  fs::{create_dir_all, read, write},
+  collections::HashMap,
+  fs::{create_dir_all, read, read_to_string, write, OpenOptions},
+  io::Write,
   path::PathBuf,
 };
 
@@ -8,7 +10,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
   extension::{Extension, ExtensionExitCode},
-  launcher::{util::theme::Theme, window::Window},
+  launcher::{
+    util::{
+      keybind::Keybind,
+      theme::{Theme, BUILTIN_THEMES},
+    },
+    window::Window,
+  },
+  provider::characters::SKIN_TONES,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +29,110 @@ pub struct Config {
   /// Keybinds used when navigating through results in the launcher
   /// These keybinds are not for opening/toggling the launcher.
   pub keybinds: Option<ConfigKeybinds>,
+  /// File and folder search, disabled when this section is missing.
+  ///
+  /// ```toml
+  /// [file_search]
+  /// directories = ["~/Documents", "~/Projects"]
+  /// ignore = [".git", "node_modules", "*.o"]
+  /// max_depth = 4
+  /// ```
+  pub file_search: Option<ConfigFileSearch>,
+  /// Shell command mode, queries starting with the prefix are run as shell commands. Disabled
+  /// unless it is enabled here.
+  ///
+  /// ```toml
+  /// [shell_mode]
+  /// enabled = true
+  /// prefix = ">"
+  /// shell = "bash"
+  /// ```
+  pub shell_mode: Option<ConfigShellMode>,
+  /// Fallback result that searches the web when nothing matches the query.
+  ///
+  /// ```toml
+  /// [web_search]
+  /// name = "DuckDuckGo"
+  /// url = "https://duckduckgo.com/?q=%s"
+  /// ```
+  pub web_search: Option<ConfigWebSearch>,
+  /// Quicklinks open a url when their keyword is typed, `%s` in the url is replaced with the rest
+  /// of the query.
+  ///
+  /// ```toml
+  /// [[quicklinks]]
+  /// keyword = "gh"
+  /// name = "GitHub"
+  /// url = "https://github.com/%s"
+  /// icon = "github"
+  /// ```
+  pub quicklinks: Option<Vec<Quicklink>>,
+  /// Clipboard history, disabled when this section is missing. Only recorded in daemon mode.
+  ///
+  /// ```toml
+  /// [clipboard_history]
+  /// keyword = "cb"
+  /// max_entries = 100
+  /// encrypt = true
+  /// ```
+  pub clipboard_history: Option<ConfigClipboardHistory>,
+  /// Switching to open windows on X11.
+  ///
+  /// ```toml
+  /// [window_switcher]
+  /// focus_existing = false
+  /// ```
+  pub window_switcher: Option<ConfigWindowSwitcher>,
+  /// Emoji and character picker, queries starting with the prefix search characters by name.
+  ///
+  /// ```toml
+  /// [characters]
+  /// prefix = ":"
+  /// skin_tone = "medium"
+  /// ```
+  pub characters: Option<ConfigCharacters>,
+  /// Lock, Log out, Suspend, Hibernate, Reboot and Shut down results. They go through logind unless
+  /// a command is set for them.
+  ///
+  /// ```toml
+  /// [session]
+  /// confirm = true
+  /// lock = "swaylock -f"
+  /// log_out = "i3-msg exit"
+  /// ```
+  pub session: Option<ConfigSession>,
+  /// Overrides for apps, keyed by the id of their desktop entry.
+  ///
+  /// ```toml
+  /// [apps]
+  /// hidden = ["org.gnome.Tour.desktop"]
+  /// pinned = ["firefox.desktop", "org.gnome.Terminal.desktop"]
+  ///
+  /// [apps.overrides."code.desktop"]
+  /// name = "VS Code"
+  /// keywords = ["editor", "vscode"]
+  /// icon = "visual-studio-code"
+  /// ```
+  pub apps: Option<ConfigApps>,
+  /// Which monitor the window is shown on and its size.
+  ///
+  /// ```toml
+  /// [window]
+  /// placement = "pointer"
+  /// offset = 0.2
+  /// width = 800
+  /// ```
+  pub window: Option<ConfigWindow>,
+  /// What the result rows show next to the name and description.
+  ///
+  /// ```toml
+  /// [results]
+  /// density = "compact"
+  /// hints = true
+  /// badges = true
+  /// preview = true
+  /// ```
+  pub results: Option<ConfigResults>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +159,9 @@ pub struct ConfigLauncher {
   /// Theme for the window
   /// Themes are located at `($XDG_CONFIG_HOME or ~/.config)/dlauncher/themes/`
   pub color_theme: String,
+  /// Theme used instead of `color_theme` when the desktop prefers a dark color scheme, read from
+  /// the portal's `color-scheme` setting or GTK's `gtk-application-prefer-dark-theme`
+  pub dark_theme: Option<String>,
   /// Number of frequent apps to show with no query
   pub frequent_apps: u16,
   /// Clear input whenever the window is shown (daemon mode)
@@ -60,21 +176,347 @@ pub struct ConfigLauncher {
   /// terminal = 'xterm -c "{}"'
   /// ```
   pub terminal_command: Option<String>,
+  /// Show the window as a layer surface through gtk-layer-shell on Wayland when it is installed,
+  /// regular Wayland windows can't be positioned or grab the keyboard. Defaults to true
+  pub layer_shell: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigWindow {
+  /// `primary` for the primary monitor, `pointer` for the monitor under the mouse pointer,
+  /// `focused` for the monitor of the focused window, or `output` for the monitor named by
+  /// `output`. Defaults to `primary`
+  pub placement: Option<String>,
+  /// Name of the monitor used with `placement = "output"`. On X11 this is the connector, e.g.
+  /// `DP-1` or `HDMI-1`, on Wayland GTK 3 only knows the monitor's model, e.g. `DELL U2720Q`. The
+  /// names are logged when no monitor has the name
+  pub output: Option<String>,
+  /// Distance between the top of the monitor and the window, as a fraction of the monitor's
+  /// height. Defaults to 0.12
+  pub offset: Option<f32>,
+  /// Space kept free below the results, as a fraction of the monitor's height. Defaults to 0.15
+  pub bottom_margin: Option<f32>,
+  /// Width of the window in pixels. Defaults to 500 scaled by the scaling factor, plus 100
+  pub width: Option<u32>,
+  /// Scaling factor used for the default width, detected from `GDK_SCALE`/`GDK_DPI_SCALE`, the
+  /// `Xft.dpi` X resource or GNOME's text scaling factor when it isn't set
+  pub scaling_factor: Option<f32>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placement {
+  Primary,
+  Pointer,
+  Focused,
+  Output(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct WindowPlacement {
+  pub placement: Placement,
+  pub offset: f32,
+  pub bottom_margin: f32,
+  pub width: Option<u32>,
+  pub scaling_factor: Option<f32>,
+}
+
+/// Keybinds are GTK accelerators like `Up`, `<Control>n` or `<Control><Shift>c`, each action takes
+/// one keybind or a list of them.
+///
+/// ```toml
+/// [keybinds]
+/// result_up = ["Up", "<Control>p", "<Control>k"]
+/// result_down = ["Down", "<Control>n", "<Control>j"]
+/// open = "Return"
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigKeybinds {
-  pub result_up: Option<String>,
-  pub result_down: Option<String>,
-  pub close: Option<String>,
-  pub open: Option<String>,
+  /// Defaults to `["Up", "<Control>p"]`
+  pub result_up: Option<ConfigKeybind>,
+  /// Defaults to `["Down", "<Control>n"]`
+  pub result_down: Option<ConfigKeybind>,
+  /// Defaults to `Escape`
+  pub close: Option<ConfigKeybind>,
+  /// Runs the selected result, modifiers held on top of the keybind are passed on to it, e.g.
+  /// Shift+Return copies a clipboard entry instead of pasting it. Defaults to `Return`
+  pub open: Option<ConfigKeybind>,
+  /// Opens the actions of the selected result, Alt+`open` does the same. Defaults to `Tab`
+  pub action_menu: Option<ConfigKeybind>,
+  /// Moves the selection a page up. Defaults to `Page_Up`
+  pub page_up: Option<ConfigKeybind>,
+  /// Moves the selection a page down. Defaults to `Page_Down`
+  pub page_down: Option<ConfigKeybind>,
+  /// Selects the first result. Defaults to `<Control>Home`
+  pub first: Option<ConfigKeybind>,
+  /// Selects the last result. Defaults to `<Control>End`
+  pub last: Option<ConfigKeybind>,
+  /// Deletes the word before the cursor. Defaults to `<Control>w`
+  pub delete_word: Option<ConfigKeybind>,
+  /// Copies the name of the selected result. Defaults to `<Control><Shift>c`
+  pub copy_name: Option<ConfigKeybind>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ConfigKeybind {
+  One(String),
+  Many(Vec<String>),
+}
+
+impl ConfigKeybind {
+  fn resolve(keybind: &Option<ConfigKeybind>, default: &[&str]) -> Vec<Keybind> {
+    let accelerators = match keybind {
+      Some(ConfigKeybind::One(accelerator)) => vec![accelerator.clone()],
+      Some(ConfigKeybind::Many(accelerators)) => accelerators.clone(),
+      None => default.iter().map(|default| default.to_string()).collect(),
+    };
+
+    Keybind::parse_all(&accelerators)
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigFileSearch {
+  /// Directories to index, `~` is expanded to the home directory. Defaults to `["~"]`
+  pub directories: Option<Vec<String>>,
+  /// Glob patterns matched against file and folder names, matches are skipped along with their
+  /// contents.
+  pub ignore: Option<Vec<String>>,
+  /// How many folders deep to index from each directory. Defaults to 4
+  pub max_depth: Option<usize>,
+  /// Maximum number of files and folders to index. Defaults to 10000
+  pub max_files: Option<usize>,
+  /// Index hidden files and folders (starting with a `.`)
+  pub show_hidden: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileSearch {
+  pub directories: Vec<PathBuf>,
+  pub ignore: Vec<String>,
+  pub max_depth: usize,
+  pub max_files: usize,
+  pub show_hidden: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigShellMode {
+  /// Enable shell command mode. Defaults to false
+  pub enabled: Option<bool>,
+  /// Prefix that starts shell command mode. Defaults to `>`
+  pub prefix: Option<String>,
+  /// Shell used to run commands. Defaults to `$SHELL`, or `sh` if it isn't set
+  pub shell: Option<String>,
+  /// History file of your shell used for completions, bash, zsh and fish histories are supported.
+  /// Defaults to `$HISTFILE`, then `~/.bash_history`, `~/.zsh_history` and fish's history.
+  pub history_file: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShellMode {
+  pub prefix: String,
+  pub shell: String,
+  pub history_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigWebSearch {
+  /// Disable the web search fallback
+  pub enabled: Option<bool>,
+  /// Name of the search engine. Defaults to `DuckDuckGo`
+  pub name: Option<String>,
+  /// Search url, `%s` is replaced with the query. Defaults to `https://duckduckgo.com/?q=%s`
+  pub url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebSearch {
+  pub name: String,
+  pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quicklink {
+  /// Keyword typed before the query, e.g. `gh` for `gh rust-lang/rust`
+  pub keyword: String,
+  /// Name shown in the results
+  pub name: String,
+  /// Url to open, `%s` is replaced with the url encoded query
+  pub url: String,
+  /// Themed icon name. Defaults to `web-browser`
+  pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigWindowSwitcher {
+  /// Disable window results
+  pub enabled: Option<bool>,
+  /// Switch to an open window of an app instead of launching a new instance, apps are matched to
+  /// windows by the `StartupWMClass` of their desktop entry. Defaults to true
+  pub focus_existing: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WindowSwitcher {
+  pub focus_existing: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigCharacters {
+  /// Disable the character picker
+  pub enabled: Option<bool>,
+  /// Prefix that starts character search. Defaults to `:`
+  pub prefix: Option<String>,
+  /// Skin tone applied to emoji that support it, one of `light`, `medium-light`, `medium`,
+  /// `medium-dark` or `dark`. Other tones can still be found by typing their name.
+  pub skin_tone: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CharacterPicker {
+  pub prefix: String,
+  /// The skin tone modifier character.
+  pub skin_tone: Option<char>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigSession {
+  /// Disable session actions
+  pub enabled: Option<bool>,
+  /// Ask for confirmation before logging out, rebooting or shutting down. Defaults to true
+  pub confirm: Option<bool>,
+  /// Command run to lock the screen instead of logind's `Lock`
+  pub lock: Option<String>,
+  /// Command run to log out instead of logind's `Terminate`
+  pub log_out: Option<String>,
+  /// Command run to suspend instead of logind's `Suspend`
+  pub suspend: Option<String>,
+  /// Command run to hibernate instead of logind's `Hibernate`
+  pub hibernate: Option<String>,
+  /// Command run to reboot instead of logind's `Reboot`
+  pub reboot: Option<String>,
+  /// Command run to shut down instead of logind's `PowerOff`
+  pub shut_down: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigApps {
+  /// Apps that are never shown in the results, apps hidden through the action menu are stored in
+  /// `dlauncher.hidden` instead
+  pub hidden: Option<Vec<String>>,
+  /// Apps always shown first, in this order, when there is no query
+  pub pinned: Option<Vec<String>>,
+  /// Custom names, keywords and icons
+  pub overrides: Option<HashMap<String, AppOverride>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigResults {
+  /// `compact` for small icons without descriptions, `comfortable` or `large` for bigger icons
+  /// and more space around them. Defaults to `comfortable`
+  pub density: Option<String>,
+  /// Show what Enter does on the selected result (e.g. `Enter to copy`) and the Alt+1 to Alt+9
+  /// shortcuts on the others. Defaults to true
+  pub hints: Option<bool>,
+  /// Show the kind of result or the extension it comes from (e.g. `File`, `Clipboard`). Defaults
+  /// to false
+  pub badges: Option<bool>,
+  /// Show a preview of the selected result next to the results when it has one (text, images,
+  /// files and folders). Defaults to false
+  pub preview: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Density {
+  Compact,
+  Comfortable,
+  Large,
+}
+
+impl Density {
+  /// Size of the result icons before scaling.
+  pub fn icon_size(&self) -> i32 {
+    match self {
+      Density::Compact => 24,
+      Density::Comfortable => 40,
+      Density::Large => 56,
+    }
+  }
+
+  /// Space above and below the name and description.
+  pub fn padding(&self) -> i32 {
+    match self {
+      Density::Compact => 2,
+      Density::Comfortable => 5,
+      Density::Large => 9,
+    }
+  }
+
+  pub fn show_description(&self) -> bool {
+    *self != Density::Compact
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultRows {
+  pub density: Density,
+  pub hints: bool,
+  pub badges: bool,
+  pub preview: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppOverride {
+  /// Name shown and matched instead of the desktop entry's name
+  pub name: Option<String>,
+  /// Other names the app is matched by
+  pub keywords: Option<Vec<String>>,
+  /// Themed icon name or absolute path to an icon
+  pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigClipboardHistory {
+  /// Keyword that shows the clipboard history, the rest of the query filters it. Defaults to `cb`
+  pub keyword: Option<String>,
+  /// Number of entries to keep. Defaults to 100
+  pub max_entries: Option<usize>,
+  /// Encrypt the history on disk with a key kept in the Secret Service keyring (GNOME Keyring,
+  /// KWallet), history is disabled when no keyring is running. The history is only readable by
+  /// you either way. Defaults to false
+  pub encrypt: Option<bool>,
+  /// Record images as well as text. Defaults to true
+  pub images: Option<bool>,
+  /// Clipboard targets that prevent an entry from being recorded, password managers like KeePassXC
+  /// mark passwords with `x-kde-passwordManagerHint`. Defaults to `["x-kde-passwordManagerHint"]`
+  pub exclude_targets: Option<Vec<String>>,
+  /// Command run after an entry is copied to paste it into the focused window, set to an empty
+  /// string to only copy. Defaults to `xdotool key --clearmodifiers ctrl+v`
+  pub paste_command: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClipboardSettings {
+  pub keyword: String,
+  pub max_entries: usize,
+  pub encrypt: bool,
+  pub images: bool,
+  pub exclude_targets: Vec<String>,
+  pub paste_command: Option<String>,
 }
 
 pub struct Keybinds {
-  pub result_up: String,
-  pub result_down: String,
-  pub close: String,
-  pub open: String,
+  pub result_up: Vec<Keybind>,
+  pub result_down: Vec<Keybind>,
+  pub close: Vec<Keybind>,
+  pub open: Vec<Keybind>,
+  pub action_menu: Vec<Keybind>,
+  pub page_up: Vec<Keybind>,
+  pub page_down: Vec<Keybind>,
+  pub first: Vec<Keybind>,
+  pub last: Vec<Keybind>,
+  pub delete_word: Vec<Keybind>,
+  pub copy_name: Vec<Keybind>,
 }
 
 impl Config {
@@ -87,12 +529,25 @@ impl Config {
       },
       launcher: ConfigLauncher {
         color_theme: "light".to_string(),
+        dark_theme: None,
         frequent_apps: 6,
         clear_input: true,
         hide_on_focus_lost: true,
         terminal_command: None,
+        layer_shell: None,
       },
       keybinds: None,
+      file_search: None,
+      shell_mode: None,
+      web_search: None,
+      quicklinks: None,
+      clipboard_history: None,
+      window_switcher: None,
+      characters: None,
+      session: None,
+      apps: None,
+      window: None,
+      results: None,
     }
   }
 
@@ -134,28 +589,264 @@ impl Config {
       result_down: None,
       close: None,
       open: None,
+      action_menu: None,
+      page_up: None,
+      page_down: None,
+      first: None,
+      last: None,
+      delete_word: None,
+      copy_name: None,
     });
 
     Keybinds {
-      result_up: k
-        .result_up
-        .as_ref()
-        .unwrap_or(&"Up".to_string())
-        .to_string(),
-      result_down: k
-        .result_down
-        .as_ref()
-        .unwrap_or(&"Down".to_string())
-        .to_string(),
-      close: k
-        .close
-        .as_ref()
-        .unwrap_or(&"Escape".to_string())
-        .to_string(),
-      open: k.open.as_ref().unwrap_or(&"Return".to_string()).to_string(),
+      result_up: ConfigKeybind::resolve(&k.result_up, &["Up", "<Control>p"]),
+      result_down: ConfigKeybind::resolve(&k.result_down, &["Down", "<Control>n"]),
+      close: ConfigKeybind::resolve(&k.close, &["Escape"]),
+      open: ConfigKeybind::resolve(&k.open, &["Return"]),
+      action_menu: ConfigKeybind::resolve(&k.action_menu, &["Tab"]),
+      page_up: ConfigKeybind::resolve(&k.page_up, &["Page_Up"]),
+      page_down: ConfigKeybind::resolve(&k.page_down, &["Page_Down"]),
+      first: ConfigKeybind::resolve(&k.first, &["<Control>Home"]),
+      last: ConfigKeybind::resolve(&k.last, &["<Control>End"]),
+      delete_word: ConfigKeybind::resolve(&k.delete_word, &["<Control>w"]),
+      copy_name: ConfigKeybind::resolve(&k.copy_name, &["<Control><Shift>c"]),
+    }
+  }
+
+  /// File search options with defaults filled in, `None` when file search is disabled.
+  pub fn file_search(&self) -> Option<FileSearch> {
+    let f = self.file_search.as_ref()?;
+    let home = PathBuf::from(std::env::var("HOME").expect("you are homeless"));
+
+    let directories = f
+      .directories
+      .clone()
+      .unwrap_or_else(|| vec!["~".to_string()])
+      .iter()
+      .map(|dir| {
+        if dir == "~" {
+          home.clone()
+        } else if let Some(rest) = dir.strip_prefix("~/") {
+          home.join(rest)
+        } else {
+          PathBuf::from(dir)
+        }
+      })
+      .collect();
+
+    Some(FileSearch {
+      directories,
+      ignore: f.ignore.clone().unwrap_or_else(|| {
+        vec![
+          ".git".to_string(),
+          "node_modules".to_string(),
+          "target".to_string(),
+          "__pycache__".to_string(),
+        ]
+      }),
+      max_depth: f.max_depth.unwrap_or(4),
+      max_files: f.max_files.unwrap_or(10000),
+      show_hidden: f.show_hidden.unwrap_or(false),
+    })
+  }
+
+  /// Shell command mode options with defaults filled in, `None` when it is disabled.
+  pub fn shell_mode(&self) -> Option<ShellMode> {
+    let s = self.shell_mode.clone().unwrap_or(ConfigShellMode {
+      enabled: None,
+      prefix: None,
+      shell: None,
+      history_file: None,
+    });
+
+    if !s.enabled.unwrap_or(false) {
+      return None;
+    }
+
+    let home = PathBuf::from(std::env::var("HOME").expect("you are homeless"));
+    let history_file = s
+      .history_file
+      .map(|file| match file.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(file),
+      })
+      .or_else(|| std::env::var("HISTFILE").ok().map(PathBuf::from))
+      .or_else(|| {
+        [
+          ".bash_history",
+          ".zsh_history",
+          ".local/share/fish/fish_history",
+        ]
+        .iter()
+        .map(|file| home.join(file))
+        .find(|file| file.exists())
+      });
+
+    Some(ShellMode {
+      prefix: s.prefix.unwrap_or_else(|| ">".to_string()),
+      shell: s
+        .shell
+        .or_else(|| std::env::var("SHELL").ok())
+        .unwrap_or_else(|| "sh".to_string()),
+      history_file,
+    })
+  }
+
+  /// Web search fallback with defaults filled in, `None` when it is disabled.
+  pub fn web_search(&self) -> Option<WebSearch> {
+    let w = self.web_search.clone().unwrap_or(ConfigWebSearch {
+      enabled: None,
+      name: None,
+      url: None,
+    });
+
+    if !w.enabled.unwrap_or(true) {
+      return None;
+    }
+
+    Some(WebSearch {
+      name: w.name.unwrap_or_else(|| "DuckDuckGo".to_string()),
+      url: w
+        .url
+        .unwrap_or_else(|| "https://duckduckgo.com/?q=%s".to_string()),
+    })
+  }
+
+  pub fn quicklinks(&self) -> Vec<Quicklink> {
+    self.quicklinks.clone().unwrap_or_default()
+  }
+
+  pub fn window_switcher(&self) -> Option<WindowSwitcher> {
+    let w = self
+      .window_switcher
+      .clone()
+      .unwrap_or(ConfigWindowSwitcher {
+        enabled: None,
+        focus_existing: None,
+      });
+
+    if !w.enabled.unwrap_or(true) {
+      return None;
+    }
+
+    Some(WindowSwitcher {
+      focus_existing: w.focus_existing.unwrap_or(true),
+    })
+  }
+
+  pub fn characters(&self) -> Option<CharacterPicker> {
+    let c = self.characters.clone().unwrap_or(ConfigCharacters {
+      enabled: None,
+      prefix: None,
+      skin_tone: None,
+    });
+
+    if !c.enabled.unwrap_or(true) {
+      return None;
+    }
+
+    let skin_tone = c.skin_tone.and_then(|tone| {
+      let modifier = SKIN_TONES
+        .iter()
+        .find(|(name, _)| *name == tone)
+        .map(|(_, modifier)| *modifier);
+      if modifier.is_none() {
+        error!("Unknown skin tone {:?}", tone);
+      }
+      modifier
+    });
+
+    Some(CharacterPicker {
+      prefix: c.prefix.unwrap_or_else(|| ":".to_string()),
+      skin_tone,
+    })
+  }
+
+  /// Session action options, `None` when they are disabled.
+  pub fn session(&self) -> Option<ConfigSession> {
+    let s = self.session.clone().unwrap_or_default();
+
+    if s.enabled.unwrap_or(true) {
+      Some(s)
+    } else {
+      None
+    }
+  }
+
+  pub fn window(&self) -> WindowPlacement {
+    let w = self.window.clone().unwrap_or_default();
+
+    let placement = match (w.placement.as_deref(), w.output) {
+      (None | Some("primary"), _) => Placement::Primary,
+      (Some("pointer"), _) => Placement::Pointer,
+      (Some("focused"), _) => Placement::Focused,
+      (Some("output"), Some(output)) => Placement::Output(output),
+      (Some("output"), None) => {
+        error!(
+          "`placement = \"output\"` needs the monitor's name in `output`, using the primary monitor"
+        );
+        Placement::Primary
+      }
+      (Some(placement), _) => {
+        error!("Invalid placement {:?}, using the primary monitor", placement);
+        Placement::Primary
+      }
+    };
+
+    WindowPlacement {
+      placement,
+      offset: w.offset.unwrap_or(0.12),
+      bottom_margin: w.bottom_margin.unwrap_or(0.15),
+      width: w.width,
+      scaling_factor: w.scaling_factor,
+    }
+  }
+
+  pub fn results(&self) -> ResultRows {
+    let r = self.results.clone().unwrap_or_default();
+
+    let density = match r.density.as_deref() {
+      Some("compact") => Density::Compact,
+      None | Some("comfortable") => Density::Comfortable,
+      Some("large") => Density::Large,
+      Some(density) => {
+        error!("Invalid density {:?}, using comfortable", density);
+        Density::Comfortable
+      }
+    };
+
+    ResultRows {
+      density,
+      hints: r.hints.unwrap_or(true),
+      badges: r.badges.unwrap_or(false),
+      preview: r.preview.unwrap_or(false),
     }
   }
 
+  pub fn apps(&self) -> ConfigApps {
+    self.apps.clone().unwrap_or_default()
+  }
+
+  /// Clipboard history options with defaults filled in, `None` when it is disabled.
+  pub fn clipboard_history(&self) -> Option<ClipboardSettings> {
+    let c = self.clipboard_history.clone()?;
+
+    Some(ClipboardSettings {
+      keyword: c.keyword.unwrap_or_else(|| "cb".to_string()),
+      max_entries: c.max_entries.unwrap_or(100),
+      encrypt: c.encrypt.unwrap_or(false),
+      images: c.images.unwrap_or(true),
+      exclude_targets: c
+        .exclude_targets
+        .unwrap_or_else(|| vec!["x-kde-passwordManagerHint".to_string()]),
+      paste_command: match c.paste_command {
+        Some(command) if command.trim().is_empty() => None,
+        Some(command) => Some(command),
+        None => Some("xdotool key --clearmodifiers ctrl+v".to_string()),
+      },
+    })
+  }
+
   pub fn dir(&self) -> PathBuf {
     PathBuf::from(std::env::var("HOME").expect("you are homeless")).join(".config/dlauncher")
   }
@@ -165,37 +856,63 @@ impl Config {
   }
 
   pub fn setup(&self) {
-    let theme_path = self.themes_dir().join("light");
-    create_dir_all(&theme_path).unwrap();
+    create_dir_all(self.themes_dir()).unwrap();
     create_dir_all(&self.dir().join("extensions")).unwrap();
     create_dir_all(&self.dir().join("scripts")).unwrap();
 
-    // Copies over default theme.
-    let default_manifest = include_bytes!("../../../data/themes/light/manifest.json");
-    let default_themecss = include_bytes!("../../../data/themes/light/theme.css");
-    let default_themegtk = include_bytes!("../../../data/themes/light/theme-gtk-3.20.css");
-    let default_resetcss = include_bytes!("../../../data/themes/light/reset.css");
-
-    write(&theme_path.join("manifest.json"), default_manifest).unwrap();
-    write(&theme_path.join("theme.css"), default_themecss).unwrap();
-    write(&theme_path.join("theme-gtk-3.20.css"), default_themegtk).unwrap();
-    write(&theme_path.join("reset.css"), default_resetcss).unwrap();
+    // Copies over the built-in themes.
+    for (name, _) in BUILTIN_THEMES {
+      if let Err(err) = Theme::install_builtin(self, name) {
+        error!("Failed to install the {} theme: {}", name, err);
+      }
+    }
   }
 
   pub fn recents(&self) -> PathBuf {
     self.dir().join("dlauncher.druncache")
   }
 
+  /// Apps hidden through the action menu, one desktop entry id per line. They're kept out of
+  /// dlauncher.toml so writing them doesn't touch the user's config.
+  pub fn hidden_apps(&self) -> PathBuf {
+    self.dir().join("dlauncher.hidden")
+  }
+
+  /// Ids of the apps hidden through the action menu.
+  pub fn read_hidden_apps(&self) -> Vec<String> {
+    read_to_string(self.hidden_apps())
+      .unwrap_or_default()
+      .lines()
+      .map(str::trim)
+      .filter(|id| !id.is_empty())
+      .map(String::from)
+      .collect()
+  }
+
+  /// Hide an app from the results from now on, `id` is the id of its desktop entry, e.g.
+  /// `firefox.desktop`.
+  pub fn hide_app(&self, id: &str) {
+    if self.read_hidden_apps().iter().any(|hidden| hidden == id) {
+      return;
+    }
+
+    let path = self.hidden_apps();
+    let result = OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(&path)
+      .and_then(|mut file| writeln!(file, "{}", id));
+    if let Err(err) = result {
+      error!(
+        "Failed to save {:?}, {} is only hidden until dlauncher restarts: {}",
+        path, id, err
+      );
+    }
+  }
+
+  /// The configured theme, the light theme when it can't be used.
   pub fn theme(&self) -> Theme {
-    let theme = read(
-      self
-        .themes_dir()
-        .join(&self.launcher.color_theme)
-        .join("manifest.json"),
-    )
-    .unwrap();
-
-    Theme::new(self.clone(), &theme)
+    Theme::load_or_fallback(self, &self.launcher.color_theme)
   }
 
   pub fn extensions(&self, window: &Window) -> Vec<Extension> {
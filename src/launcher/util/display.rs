@@ -1,28 +1,169 @@
+use std::{cell::RefCell, sync::Once};
+
 use gtk::gdk::{Display, Monitor, prelude::*};
-use gtk::gio::Settings;
+use gtk::gio::{Settings, SettingsSchemaSource};
+use log::error;
+
+use super::{config::Placement, xresources::resource};
+
+const GNOME_INTERFACE: &str = "org.gnome.desktop.interface";
 
-pub fn monitor() -> Monitor {
+thread_local! {
+  /// The `Xft.dpi` X resource, read once and again after the XSETTINGS DPI changed.
+  static XFT_DPI: RefCell<Option<Option<f32>>> = const { RefCell::new(None) };
+}
+static WATCH_XSETTINGS: Once = Once::new();
+
+/// The monitor the window is placed on, the primary monitor is used when the placement's monitor
+/// can't be found (e.g. the focused window on Wayland).
+pub fn monitor(placement: &Placement) -> Monitor {
   let display = Display::default().unwrap();
-  if let Some(monitor) = display.primary_monitor() {
+  let placed = match placement {
+    Placement::Primary => None,
+    Placement::Pointer => pointer_monitor(&display),
+    Placement::Focused => focused_monitor(&display),
+    Placement::Output(name) => output_monitor(&display, name),
+  };
+
+  if let Some(monitor) = placed {
+    monitor
+  } else if let Some(monitor) = display.primary_monitor() {
     monitor
   } else if let Some(monitor) = display.monitor(0) {
     monitor
+  } else if let Some(monitor) = pointer_monitor(&display) {
+    monitor
   } else {
-    let seat = display.default_seat().unwrap();
-    let (_, x, y) = seat.pointer().unwrap().position();
+    panic!("Couldn't get monitor through various methods...")
+  }
+}
+
+/// The monitor named `name`. GDK's model is the connector name on X11 (`DP-1`), but the model
+/// reported by the monitor on Wayland, where GTK 3 doesn't expose connector names.
+fn output_monitor(display: &Display, name: &str) -> Option<Monitor> {
+  let monitors = (0..display.n_monitors())
+    .filter_map(|i| display.monitor(i))
+    .collect::<Vec<Monitor>>();
+
+  let monitor = monitors
+    .iter()
+    .find(|monitor| monitor.model().map(|model| model == name).unwrap_or(false))
+    .cloned();
+  if monitor.is_none() {
+    let names = monitors
+      .iter()
+      .filter_map(|monitor| monitor.model().map(|model| model.to_string()))
+      .collect::<Vec<String>>();
+    error!(
+      "No monitor named {:?}, using the primary monitor. Monitors: {:?}",
+      name, names
+    );
+  }
+
+  monitor
+}
+
+// `gdk_screen_get_active_window` has no replacement in GTK 3, it reads `_NET_ACTIVE_WINDOW` on X11
+// and is `None` on Wayland
+#[allow(deprecated)]
+fn focused_monitor(display: &Display) -> Option<Monitor> {
+  let window = display.default_screen().active_window()?;
+
+  display.monitor_at_window(&window)
+}
+
+fn pointer_monitor(display: &Display) -> Option<Monitor> {
+  let seat = display.default_seat()?;
+  let (_, x, y) = seat.pointer()?.position();
+
+  display.monitor_at_point(x, y)
+}
+
+/// How much bigger than at 96 DPI the window is drawn, taken from the first of these that is set:
+/// `scaling_factor` in the config, `GDK_SCALE` and `GDK_DPI_SCALE`, the `Xft.dpi` X resource and
+/// GNOME's text scaling factor. The monitor's scale factor is used when none are.
+pub fn scaling_factor(monitor: &Monitor, configured: Option<f32>) -> f32 {
+  if let Some(scale) = configured {
+    return scale;
+  }
 
-    if let Some(monitor) = display.monitor_at_point(x, y) {
-      monitor
-    } else {
-      panic!("Couldn't get monitor through various methods...")
+  let env_scale = |name| std::env::var(name).ok().and_then(|scale| scale.parse::<f32>().ok());
+  match (env_scale("GDK_SCALE"), env_scale("GDK_DPI_SCALE")) {
+    (None, None) => {}
+    (scale, dpi_scale) => {
+      return scale.unwrap_or(monitor.scale_factor() as f32) * dpi_scale.unwrap_or(1.0);
     }
   }
+
+  if let Some(dpi) = xft_dpi() {
+    return dpi / 96.0;
+  }
+
+  // `Settings::new` aborts when the schema isn't installed, which is common outside of GNOME
+  let has_schema = SettingsSchemaSource::default()
+    .and_then(|source| source.lookup(GNOME_INTERFACE, true))
+    .is_some();
+  if has_schema {
+    let text_scaling = Settings::new(GNOME_INTERFACE).double("text-scaling-factor");
+    return (monitor.scale_factor() as f64 * text_scaling) as f32;
+  }
+
+  monitor.scale_factor() as f32
+}
+
+fn xft_dpi() -> Option<f32> {
+  WATCH_XSETTINGS.call_once(|| {
+    if let Some(settings) = gtk::Settings::default() {
+      // DPI daemons like xsettingsd update `Xft.dpi` along with it
+      settings.connect_notify_local(Some("gtk-xft-dpi"), |_, _| {
+        XFT_DPI.with(|dpi| dpi.borrow_mut().take());
+      });
+    }
+  });
+
+  XFT_DPI.with(|dpi| {
+    *dpi
+      .borrow_mut()
+      .get_or_insert_with(|| resource("Xft.dpi").and_then(|dpi| dpi.parse::<f32>().ok()))
+  })
+}
+
+/// How icons are sized on the monitor the window is shown on.
+#[derive(Debug, Clone, Copy)]
+pub struct IconScale {
+  /// Scaling GTK doesn't apply itself, e.g. from `Xft.dpi` or GNOME's text scaling factor.
+  pub logical: f32,
+  /// GTK's integer scale factor of the monitor, icons are loaded this many times bigger than they
+  /// are shown so they stay sharp.
+  pub device: i32,
 }
 
-pub fn scaling_factor() -> f32 {
-  let monitor_scaling = monitor().scale_factor();
-  let text_scaling = Settings::new("org.gnome.desktop.interface");
-  let text_scaling = text_scaling.double("text-scaling-factor");
+impl IconScale {
+  pub fn new(monitor: &Monitor, scaling_factor: f32) -> Self {
+    let device = monitor.scale_factor().max(1);
 
-  (monitor_scaling as f64 * text_scaling) as f32
-}
\ No newline at end of file
+    Self {
+      logical: (scaling_factor / device as f32).max(1.0),
+      device,
+    }
+  }
+
+  /// Size in application pixels an icon of `size` is shown at.
+  pub fn logical_size(&self, size: i32) -> i32 {
+    (size as f32 * self.logical).round() as i32
+  }
+
+  /// Size in device pixels an icon of `size` is loaded at.
+  pub fn pixel_size(&self, size: i32) -> i32 {
+    self.logical_size(size) * self.device
+  }
+}
+
+impl Default for IconScale {
+  fn default() -> Self {
+    Self {
+      logical: 1.0,
+      device: 1,
+    }
+  }
+}
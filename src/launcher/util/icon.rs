@@ -1,46 +1,272 @@
-use gtk::{gdk_pixbuf::Pixbuf, prelude::*, IconLookupFlags, IconTheme};
+use std::{
+  cell::RefCell,
+  collections::{hash_map::DefaultHasher, HashMap},
+  hash::{Hash, Hasher},
+};
 
-/// Get a themed icon's specific path on the filesystem.
-pub fn get_icon_path(icon: &str, size: i32) -> String {
-  let icon_theme = IconTheme::default().unwrap();
+use gtk::{
+  cairo::{Context, Format, ImageSurface},
+  gdk::pixbuf_get_from_surface,
+  gdk_pixbuf::{Colorspace, InterpType, Pixbuf, PixbufLoader},
+  gio::{Cancellable, Icon, LoadableIcon},
+  glib::Bytes,
+  pango::{FontDescription, SCALE},
+  prelude::*,
+  IconLookupFlags, IconTheme, Image, Label,
+};
+use log::{debug, error};
 
+/// Shown for icons that can't be loaded, when the icon theme has no `dialog-question-symbolic`.
+const FALLBACK_ICON: &[u8] = include_bytes!("../../../data/icons/fallback.svg");
+
+/// What a cached icon was loaded from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum IconSource {
+  /// A themed icon name or an absolute path.
+  Named(String),
+  /// A serialized `gio::Icon`.
+  GIcon(String),
+  /// The hash of an SVG document.
+  Svg(u64),
+  /// The icon shown when another one can't be loaded.
+  Fallback,
+}
+
+/// Icons kept in the cache, the least recently used one is dropped to make room for another.
+const CACHE_SIZE: usize = 512;
+
+/// Decoded icons by their source and size in device pixels, which already has the monitor's scale
+/// applied, with when they were last used.
+#[derive(Default)]
+struct IconCache {
+  icons: HashMap<(IconSource, i32), (Pixbuf, u64)>,
+  uses: u64,
+}
+
+impl IconCache {
+  fn get(&mut self, key: &(IconSource, i32)) -> Option<Pixbuf> {
+    self.uses += 1;
+    let (pixbuf, used) = self.icons.get_mut(key)?;
+    *used = self.uses;
+    Some(pixbuf.clone())
+  }
+
+  fn insert(&mut self, key: (IconSource, i32), pixbuf: Pixbuf) {
+    if self.icons.len() >= CACHE_SIZE {
+      let least_used = self
+        .icons
+        .iter()
+        .min_by_key(|(_, (_, used))| *used)
+        .map(|(key, _)| key.clone());
+      if let Some(least_used) = least_used {
+        self.icons.remove(&least_used);
+      }
+    }
+
+    self.uses += 1;
+    self.icons.insert(key, (pixbuf, self.uses));
+  }
+}
+
+thread_local! {
+  /// Cleared when the icon theme changes.
+  static CACHE: RefCell<IconCache> = {
+    if let Some(theme) = IconTheme::default() {
+      theme.connect_changed(|_| {
+        debug!("The icon theme changed, clearing the icon cache");
+        CACHE.with(|cache| cache.borrow_mut().icons.clear());
+      });
+    }
+
+    RefCell::new(IconCache::default())
+  };
+}
+
+fn cached<F: FnOnce() -> Pixbuf>(source: IconSource, size: i32, load: F) -> Pixbuf {
+  let key = (source, size);
+  if let Some(pixbuf) = CACHE.with(|cache| cache.borrow_mut().get(&key)) {
+    return pixbuf;
+  }
+
+  // not borrowed while loading, loading can fall back to another cached icon
+  let pixbuf = load();
+  CACHE.with(|cache| cache.borrow_mut().insert(key, pixbuf.clone()));
+
+  pixbuf
+}
+
+/// Get a themed icon's specific path on the filesystem, `None` when the icon theme doesn't have
+/// it.
+pub fn get_icon_path(icon: &str, size: i32) -> Option<String> {
   if icon.starts_with('/') {
-    icon.to_string()
-  } else if let Some(themed_icon) = icon_theme.lookup_icon(icon, size, IconLookupFlags::FORCE_SIZE)
-  {
-    themed_icon
-      .filename()
-      .unwrap()
-      .to_string_lossy()
-      .to_string()
-  } else {
-    icon_theme
-      .lookup_icon(
-        "dialog-question-symbolic",
-        size,
-        IconLookupFlags::FORCE_SIZE,
-      )
-      .unwrap()
-      .filename()
-      .unwrap()
-      .to_string_lossy()
-      .to_string()
+    return Some(icon.to_string());
   }
+
+  IconTheme::default()?
+    .lookup_icon(icon, size, IconLookupFlags::FORCE_SIZE)?
+    .filename()
+    .map(|path| path.to_string_lossy().to_string())
 }
 
-/// Load a themed icon with a specified size.
+/// Load a themed icon with a specified size. Absolute paths and serialized `gio::Icon`s (e.g. the
+/// `Icon` of a desktop entry with emblems) work too.
 pub fn load_icon(icon: &str, size: i32) -> Pixbuf {
-  let icon_path = get_icon_path(icon, size);
+  cached(IconSource::Named(icon.to_string()), size, || {
+    let from_path = get_icon_path(icon, size)
+      .and_then(|path| Pixbuf::from_file_at_size(&path, size, size).ok());
+    if let Some(pixbuf) = from_path {
+      return pixbuf;
+    }
+
+    // serialized icons like `. GThemedIcon name fallback-name` or `file://` URIs
+    let from_gicon = match Icon::for_string(icon) {
+      Ok(gicon) if !icon.starts_with('/') => try_load_gicon(&gicon, size),
+      _ => None,
+    };
+    from_gicon.unwrap_or_else(|| {
+      debug!("Couldn't load the icon {:?}", icon);
+      default_pixbuf(size)
+    })
+  })
+}
+
+/// Load a `gio::Icon` (themed icons with fallback names, file icons, emblemed icons, icons
+/// holding image data, etc.) with a specified size.
+pub fn load_gicon(icon: &Icon, size: i32) -> Pixbuf {
+  let load = || {
+    try_load_gicon(icon, size).unwrap_or_else(|| {
+      debug!("Couldn't load the icon {:?}", IconExt::to_string(icon));
+      default_pixbuf(size)
+    })
+  };
 
-  if let Ok(pixbuf) = Pixbuf::from_file_at_size(&icon_path, size, size) {
-    pixbuf
+  // icons that can't be serialized, e.g. ones holding image data, aren't cached
+  match IconExt::to_string(icon) {
+    Some(key) => cached(IconSource::GIcon(key.to_string()), size, load),
+    None => load(),
+  }
+}
+
+fn try_load_gicon(icon: &Icon, size: i32) -> Option<Pixbuf> {
+  let themed = IconTheme::default()
+    .and_then(|theme| theme.lookup_by_gicon(icon, size, IconLookupFlags::FORCE_SIZE))
+    .and_then(|info| info.load_icon().ok());
+  if themed.is_some() {
+    return themed;
+  }
+
+  // file and bytes icons the icon theme couldn't load, e.g. in a format it doesn't know
+  let loadable = icon.dynamic_cast_ref::<LoadableIcon>()?;
+  let (stream, _) = loadable.load(size, None::<&Cancellable>).ok()?;
+
+  Pixbuf::from_stream_at_scale(&stream, size, size, true, None::<&Cancellable>).ok()
+}
+
+/// Render an SVG document, e.g. an icon sent by an extension, with a specified size. Invalid
+/// documents show the fallback icon.
+pub fn load_svg(svg: &str, size: i32) -> Pixbuf {
+  let mut hasher = DefaultHasher::new();
+  svg.hash(&mut hasher);
+
+  cached(IconSource::Svg(hasher.finish()), size, || {
+    decode_svg(svg.as_bytes(), size).unwrap_or_else(|err| {
+      error!("Invalid SVG icon: {}", err);
+      default_pixbuf(size)
+    })
+  })
+}
+
+fn decode_svg(svg: &[u8], size: i32) -> Result<Pixbuf, String> {
+  let loader = PixbufLoader::new();
+  loader.set_size(size, size);
+
+  let written = loader.write(svg);
+  // the loader has to be closed even when writing failed, it warns when it's dropped otherwise
+  let closed = loader.close();
+  written.and(closed).map_err(|err| err.to_string())?;
+
+  loader
+    .pixbuf()
+    .ok_or_else(|| "the image is empty".to_string())
+}
+
+/// Render a character (or any short text) centered in a square icon, used to show emoji and other
+/// characters as result icons.
+pub fn glyph_pixbuf(text: &str, size: i32) -> Pixbuf {
+  let render = || {
+    let surface = ImageSurface::create(Format::ARgb32, size, size).ok()?;
+    let cr = Context::new(&surface).ok()?;
+
+    // an unrealized label is only used for its pango context and style
+    let label = Label::new(None);
+    let layout = label.create_pango_layout(Some(text));
+    let mut font = FontDescription::new();
+    font.set_absolute_size(size as f64 * 0.75 * SCALE as f64);
+    layout.set_font_description(Some(&font));
+
+    let (width, height) = layout.pixel_size();
+    gtk::render_layout(
+      &label.style_context(),
+      &cr,
+      (size - width) as f64 / 2.0,
+      (size - height) as f64 / 2.0,
+      &layout,
+    );
+    drop(cr);
+
+    pixbuf_get_from_surface(&surface, 0, 0, size, size)
+  };
+
+  render().unwrap_or_else(|| default_pixbuf(size))
+}
+
+/// Show a pixbuf loaded at `scale` times the size it's shown at, so it stays sharp on HiDPI
+/// monitors.
+pub fn set_scaled_pixbuf(image: &Image, pixbuf: &Pixbuf, scale: i32) {
+  let surface = if scale > 1 {
+    pixbuf.create_surface(scale, image.window().as_ref())
   } else {
-    default_pixbuf(size)
+    None
+  };
+
+  match surface {
+    Some(surface) => image.set_from_surface(Some(&surface)),
+    None => image.set_from_pixbuf(Some(pixbuf)),
   }
 }
 
+/// The icon shown when another one can't be loaded: `dialog-question-symbolic` from the icon theme,
+/// an icon built into dlauncher when the theme doesn't have it, or a transparent square when even
+/// that can't be decoded (e.g. without an SVG loader for gdk-pixbuf).
 pub fn default_pixbuf(size: i32) -> Pixbuf {
-  let icon_path = get_icon_path("dialog-question-symbolic", size);
+  let size = size.max(1);
+
+  cached(IconSource::Fallback, size, || {
+    get_icon_path("dialog-question-symbolic", size)
+      .and_then(|path| Pixbuf::from_file_at_size(&path, size, size).ok())
+      .or_else(|| match decode_svg(FALLBACK_ICON, size) {
+        Ok(pixbuf) => Some(pixbuf),
+        Err(err) => {
+          error!("Couldn't load the fallback icon: {}", err);
+          None
+        }
+      })
+      .unwrap_or_else(|| transparent_pixbuf(size))
+  })
+}
+
+fn transparent_pixbuf(size: i32) -> Pixbuf {
+  let pixel = Pixbuf::from_bytes(
+    &Bytes::from_static(&[0, 0, 0, 0]),
+    Colorspace::Rgb,
+    true,
+    8,
+    1,
+    1,
+    4,
+  );
 
-  Pixbuf::from_file_at_size(&icon_path, size, size).unwrap()
+  pixel
+    .scale_simple(size, size, InterpType::Nearest)
+    .unwrap_or(pixel)
 }
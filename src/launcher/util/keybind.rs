@@ -0,0 +1,65 @@
+use gtk::{
+  accelerator_get_default_mod_mask, accelerator_parse,
+  gdk::{
+    keys::{constants as keys, Key},
+    EventKey, ModifierType,
+  },
+};
+use log::error;
+
+/// A key with the modifiers that have to be held, parsed from a GTK accelerator string like
+/// `<Control>n`, `<Shift><Alt>Return` or just `Up`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keybind {
+  pub key: Key,
+  pub modifiers: ModifierType,
+}
+
+impl Keybind {
+  pub fn parse(accelerator: &str) -> Option<Self> {
+    let (key, modifiers) = accelerator_parse(accelerator);
+
+    if key == 0 {
+      error!("Invalid keybind {:?}", accelerator);
+      return None;
+    }
+
+    Some(Self {
+      key: Key::from(key).to_lower(),
+      modifiers,
+    })
+  }
+
+  /// Parse a list of accelerators, invalid ones are logged and skipped.
+  pub fn parse_all(accelerators: &[String]) -> Vec<Self> {
+    accelerators
+      .iter()
+      .filter_map(|accelerator| Keybind::parse(accelerator))
+      .collect()
+  }
+
+  /// The pressed key with its relevant modifiers, lock keys like Num Lock are ignored.
+  pub fn from_event(event: &EventKey) -> Self {
+    let key = match event.keyval() {
+      // Shift+Tab is reported as its own key
+      keys::ISO_Left_Tab => keys::Tab,
+      key => key,
+    };
+
+    Self {
+      key: key.to_lower(),
+      modifiers: event.state() & accelerator_get_default_mod_mask(),
+    }
+  }
+
+  /// Whether the key is pressed with exactly the modifiers of the keybind.
+  pub fn matches(&self, pressed: &Keybind) -> bool {
+    self.key == pressed.key && self.modifiers == pressed.modifiers
+  }
+
+  /// Whether the key is pressed with at least the modifiers of the keybind, used for `open` where
+  /// the extra modifiers are passed on to the result (e.g. Shift+Return to copy).
+  pub fn matches_loosely(&self, pressed: &Keybind) -> bool {
+    self.key == pressed.key && pressed.modifiers.contains(self.modifiers)
+  }
+}
@@ -0,0 +1,164 @@
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+
+use dbus::{
+  arg::{PropMap, RefArg, Variant},
+  blocking::Connection,
+  message::MatchRule,
+  Path,
+};
+use log::debug;
+
+const SECRETS: &str = "org.freedesktop.secrets";
+const SECRETS_PATH: &str = "/org/freedesktop/secrets";
+const SERVICE: &str = "org.freedesktop.Secret.Service";
+const ITEM: &str = "org.freedesktop.Secret.Item";
+const PROMPT: &str = "org.freedesktop.Secret.Prompt";
+/// The collection new secrets are stored in, usually the login keyring.
+const DEFAULT_COLLECTION: &str = "/org/freedesktop/secrets/aliases/default";
+/// How long to wait for the user to unlock the keyring.
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A `(session, parameters, value, content type)` secret, as the Secret Service sends them.
+type Secret = (Path<'static>, Vec<u8>, Vec<u8>, String);
+
+/// A secret stored in the Secret Service keyring (GNOME Keyring, KWallet, KeePassXC), found by
+/// its `dlauncher` attribute.
+pub struct Keyring {
+  conn: Connection,
+  session: Path<'static>,
+  attributes: HashMap<String, String>,
+}
+
+impl Keyring {
+  /// Connect to the Secret Service, `name` identifies the secret among dlauncher's secrets.
+  pub fn new(name: &str) -> Result<Self, dbus::Error> {
+    let conn = Connection::new_session()?;
+
+    // secrets are sent unencrypted over the session bus, which only the user can connect to
+    let (_, session): (Variant<Box<dyn RefArg>>, Path<'static>) = conn
+      .with_proxy(SECRETS, SECRETS_PATH, Duration::from_millis(5000))
+      .method_call(SERVICE, "OpenSession", ("plain", Variant("")))?;
+
+    Ok(Self {
+      conn,
+      session,
+      attributes: HashMap::from([("dlauncher".to_string(), name.to_string())]),
+    })
+  }
+
+  /// The stored secret, unlocking the keyring first when it is locked. `None` when there is no
+  /// secret yet.
+  pub fn get(&self) -> Result<Option<Vec<u8>>, dbus::Error> {
+    let (unlocked, locked): (Vec<Path<'static>>, Vec<Path<'static>>) =
+      self
+        .service()
+        .method_call(SERVICE, "SearchItems", (self.attributes.clone(),))?;
+
+    let item = match (unlocked.into_iter().next(), locked.into_iter().next()) {
+      (Some(item), _) => item,
+      (None, Some(item)) => {
+        self.unlock(item.clone())?;
+        item
+      }
+      (None, None) => return Ok(None),
+    };
+
+    let (secret,): (Secret,) = self
+      .conn
+      .with_proxy(SECRETS, item, Duration::from_millis(5000))
+      .method_call(ITEM, "GetSecret", (self.session.clone(),))?;
+
+    Ok(Some(secret.2))
+  }
+
+  /// Store a secret in the default collection, `label` is what keyring managers show for it.
+  pub fn set(&self, label: &str, secret: &[u8]) -> Result<(), dbus::Error> {
+    self.unlock(Path::from(DEFAULT_COLLECTION))?;
+
+    let mut properties = PropMap::new();
+    properties.insert(
+      "org.freedesktop.Secret.Item.Label".to_string(),
+      Variant(Box::new(label.to_string())),
+    );
+    properties.insert(
+      "org.freedesktop.Secret.Item.Attributes".to_string(),
+      Variant(Box::new(self.attributes.clone())),
+    );
+    let secret: Secret = (
+      self.session.clone(),
+      vec![],
+      secret.to_vec(),
+      "application/octet-stream".to_string(),
+    );
+
+    let (_, prompt): (Path<'static>, Path<'static>) = self
+      .conn
+      .with_proxy(SECRETS, DEFAULT_COLLECTION, Duration::from_millis(5000))
+      .method_call(
+        "org.freedesktop.Secret.Collection",
+        "CreateItem",
+        (properties, secret, true),
+      )?;
+
+    self.prompt(prompt)
+  }
+
+  fn service(&self) -> dbus::blocking::Proxy<'_, &Connection> {
+    self
+      .conn
+      .with_proxy(SECRETS, SECRETS_PATH, Duration::from_millis(5000))
+  }
+
+  fn unlock(&self, object: Path<'static>) -> Result<(), dbus::Error> {
+    let (_, prompt): (Vec<Path<'static>>, Path<'static>) =
+      self
+        .service()
+        .method_call(SERVICE, "Unlock", (vec![object],))?;
+
+    self.prompt(prompt)
+  }
+
+  /// Show a prompt, e.g. the keyring password dialog, and wait until the user answered it.
+  fn prompt(&self, prompt: Path<'static>) -> Result<(), dbus::Error> {
+    if &*prompt == "/" {
+      return Ok(());
+    }
+
+    debug!("Waiting for the keyring prompt");
+    let completed = Arc::new(Mutex::new(None));
+    let on_completed = completed.clone();
+    let rule = MatchRule::new_signal(PROMPT, "Completed").with_path(prompt.clone());
+    self.conn.add_match(
+      rule,
+      move |(dismissed, _): (bool, Variant<Box<dyn RefArg>>), _, _| {
+        *on_completed.lock().unwrap() = Some(dismissed);
+        false
+      },
+    )?;
+
+    self
+      .conn
+      .with_proxy(SECRETS, prompt, Duration::from_millis(5000))
+      .method_call::<(), _, _, _>(PROMPT, "Prompt", ("",))?;
+
+    let start = Instant::now();
+    loop {
+      self.conn.process(Duration::from_millis(500))?;
+
+      match *completed.lock().unwrap() {
+        Some(false) => return Ok(()),
+        Some(true) => return Err(dbus::Error::new_failed("The keyring prompt was dismissed")),
+        None if start.elapsed() > PROMPT_TIMEOUT => {
+          return Err(dbus::Error::new_failed(
+            "Timed out waiting for the keyring prompt",
+          ))
+        }
+        None => {}
+      }
+    }
+  }
+}
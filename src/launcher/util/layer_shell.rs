@@ -0,0 +1,132 @@
+use std::os::raw::{c_char, c_int};
+
+use gtk::{
+  ffi::GtkWindow,
+  gdk::{ffi::GdkMonitor, Display, Monitor},
+  glib::{ffi::gboolean, translate::ToGlibPtr},
+  prelude::*,
+  Window,
+};
+use libloading::Library;
+use log::{debug, error};
+
+const LAYER_OVERLAY: c_int = 3;
+const EDGE_TOP: c_int = 2;
+const KEYBOARD_MODE_EXCLUSIVE: c_int = 1;
+
+/// gtk-layer-shell, loaded at runtime so dlauncher doesn't depend on it. With it the window is a
+/// layer surface on Wayland compositors that support `wlr-layer-shell` (sway, Hyprland, KDE, ...),
+/// which can be placed and grab the keyboard, regular Wayland windows can do neither.
+#[derive(Debug)]
+pub struct LayerShell {
+  /// Keeps the functions below loaded.
+  _library: Library,
+  init_for_window: unsafe extern "C" fn(*mut GtkWindow),
+  set_namespace: unsafe extern "C" fn(*mut GtkWindow, *const c_char),
+  set_layer: unsafe extern "C" fn(*mut GtkWindow, c_int),
+  set_anchor: unsafe extern "C" fn(*mut GtkWindow, c_int, gboolean),
+  /// `gtk_layer_set_keyboard_mode`, or `gtk_layer_set_keyboard_interactivity` which it replaced
+  /// in 0.6. Both take 1 for exclusive keyboard focus.
+  set_keyboard_mode: unsafe extern "C" fn(*mut GtkWindow, c_int),
+  set_monitor: unsafe extern "C" fn(*mut GtkWindow, *mut GdkMonitor),
+  set_margin: unsafe extern "C" fn(*mut GtkWindow, c_int, c_int),
+}
+
+impl LayerShell {
+  /// Load gtk-layer-shell when running on Wayland, `None` on X11 or when it isn't installed or
+  /// supported by the compositor, the window is positioned the X11 way then.
+  pub fn new() -> Option<Self> {
+    let display = Display::default()?;
+    if display.type_().name() != "GdkWaylandDisplay" {
+      return None;
+    }
+
+    let library = match unsafe { Library::new("libgtk-layer-shell.so.0") } {
+      Ok(library) => library,
+      Err(err) => {
+        debug!("gtk-layer-shell isn't available: {}", err);
+        return None;
+      }
+    };
+
+    // `gtk_layer_is_supported` was added in 0.6, older versions have to be trusted
+    let is_supported =
+      unsafe { symbol::<unsafe extern "C" fn() -> gboolean>(&library, "is_supported") };
+    if let Some(is_supported) = is_supported {
+      if unsafe { is_supported() } == 0 {
+        error!("The compositor doesn't support wlr-layer-shell, falling back to a regular window");
+        return None;
+      }
+    }
+
+    let layer_shell = unsafe {
+      Self {
+        init_for_window: required(&library, "init_for_window")?,
+        set_namespace: required(&library, "set_namespace")?,
+        set_layer: required(&library, "set_layer")?,
+        set_anchor: required(&library, "set_anchor")?,
+        set_keyboard_mode: match symbol(&library, "set_keyboard_mode") {
+          Some(set_keyboard_mode) => set_keyboard_mode,
+          None => required(&library, "set_keyboard_interactivity")?,
+        },
+        set_monitor: required(&library, "set_monitor")?,
+        set_margin: required(&library, "set_margin")?,
+        _library: library,
+      }
+    };
+
+    debug!("Using gtk-layer-shell");
+    Some(layer_shell)
+  }
+
+  /// Turn the window into an overlay anchored to the top center of the focused output with
+  /// exclusive keyboard focus, this has to happen before it is first shown.
+  pub fn init(&self, window: &Window) {
+    let window: *mut GtkWindow = window.to_glib_none().0;
+
+    unsafe {
+      (self.init_for_window)(window);
+      (self.set_namespace)(window, c"dlauncher".as_ptr());
+      (self.set_layer)(window, LAYER_OVERLAY);
+      // only anchoring the top edge centers the window horizontally
+      (self.set_anchor)(window, EDGE_TOP, 1);
+      (self.set_keyboard_mode)(window, KEYBOARD_MODE_EXCLUSIVE);
+    }
+  }
+
+  /// Output the window is shown on, `None` lets the compositor pick the focused output.
+  pub fn set_monitor(&self, window: &Window, monitor: Option<&Monitor>) {
+    let window: *mut GtkWindow = window.to_glib_none().0;
+    let monitor: *mut GdkMonitor = monitor.to_glib_none().0;
+
+    unsafe { (self.set_monitor)(window, monitor) };
+  }
+
+  /// Distance between the top of the output and the window.
+  pub fn set_top_margin(&self, window: &Window, margin: i32) {
+    let window: *mut GtkWindow = window.to_glib_none().0;
+
+    unsafe { (self.set_margin)(window, EDGE_TOP, margin) };
+  }
+}
+
+/// Look up `gtk_layer_<name>`, `T` has to be its exact function pointer type.
+unsafe fn symbol<T: Copy>(library: &Library, name: &str) -> Option<T> {
+  let symbol = format!("gtk_layer_{}\0", name);
+  library
+    .get::<T>(symbol.as_bytes())
+    .ok()
+    .map(|function| *function)
+}
+
+/// Like `symbol`, for functions every supported gtk-layer-shell version has.
+unsafe fn required<T: Copy>(library: &Library, name: &str) -> Option<T> {
+  let function = symbol(library, name);
+  if function.is_none() {
+    error!(
+      "gtk-layer-shell doesn't have gtk_layer_{}, falling back to a regular window",
+      name
+    );
+  }
+  function
+}
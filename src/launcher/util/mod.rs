@@ -2,7 +2,11 @@ pub mod app;
 pub mod config;
 pub mod dirs;
 pub mod icon;
+pub mod keybind;
+pub mod layer_shell;
 pub mod query_history;
 pub mod recent;
 pub mod theme;
 pub mod display;
+pub mod keyring;
+pub mod xresources;
@@ -1,12 +1,68 @@
 use std::{
-  fs::{read, read_to_string, write},
-  path::PathBuf,
+  cell::{Cell, RefCell},
+  collections::HashMap,
+  fmt,
+  fs::{create_dir_all, read, read_dir, remove_dir_all, write},
+  io,
+  path::{Component, Path, PathBuf},
+  rc::Rc,
+  time::Duration,
 };
 
+use dbus::{
+  arg::{RefArg, Variant},
+  blocking::Connection,
+  message::MatchRule,
+};
+use gtk::{
+  gdk::Screen,
+  gio::{File, FileMonitor, FileMonitorFlags},
+  glib::{self, timeout_add_local_once, MainContext, PRIORITY_DEFAULT},
+  prelude::*,
+  traits::SettingsExt as _,
+  CssProvider, Settings,
+};
+use log::{debug, error};
 use serde::{Deserialize, Serialize};
 
 use crate::launcher::util::config::Config;
 
+macro_rules! builtin_theme {
+  ($name:literal, [$($file:literal),*]) => {
+    (
+      $name,
+      &[$((
+        $file,
+        include_bytes!(concat!("../../../data/themes/", $name, "/", $file)) as &[u8],
+      )),*],
+    )
+  };
+}
+
+const PORTAL_SETTINGS: &str = "org.freedesktop.portal.Settings";
+/// The portal settings namespace `color-scheme` is in.
+const APPEARANCE: &str = "org.freedesktop.appearance";
+
+/// A built-in theme's name and its files, the manifest first.
+pub type BuiltinTheme = (&'static str, &'static [(&'static str, &'static [u8])]);
+
+/// Themes shipped with dlauncher and their files, they are written to the themes directory on the
+/// first run and by `dlauncher-themes install`.
+pub const BUILTIN_THEMES: &[BuiltinTheme] = &[
+  builtin_theme!(
+    "light",
+    [
+      "manifest.json",
+      "theme.css",
+      "theme-gtk-3.20.css",
+      "reset.css"
+    ]
+  ),
+  builtin_theme!("dark", ["manifest.json", "theme.css"]),
+  builtin_theme!("high-contrast", ["manifest.json", "theme.css"]),
+  builtin_theme!("adaptive", ["manifest.json", "theme.css"]),
+];
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ThemeJson {
   pub manifest_version: String,
@@ -17,32 +73,232 @@ pub struct ThemeJson {
   #[serde(rename = "css_file_gtk_3.20+")]
   pub css_file_gtk_3_20: Option<String>,
   pub matched_text_hl_colors: MatchedTextHlColors,
+  /// Colors defined with `@define-color` after the theme's CSS, overriding the colors it defines.
+  ///
+  /// ```json
+  /// "variables": {
+  ///   "bg_color": "#1e1e2e",
+  ///   "item_name": "#cdd6f4"
+  /// }
+  /// ```
+  #[serde(default)]
+  pub variables: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MatchedTextHlColors {
   pub when_selected: String,
   pub when_not_selected: String,
+  /// Show matched characters in bold as well.
+  #[serde(default)]
+  pub bold: bool,
+  /// Underline matched characters as well.
+  #[serde(default)]
+  pub underline: bool,
 }
 
+/// Manifest versions of Ulauncher themes that dlauncher understands.
+const MANIFEST_VERSIONS: [&str; 1] = ["1"];
+
+/// Why a theme can't be used, the messages say how to fix it.
+#[derive(Debug)]
+pub enum ThemeError {
+  NotInstalled {
+    name: String,
+    path: PathBuf,
+  },
+  Unreadable {
+    path: PathBuf,
+    err: io::Error,
+  },
+  Unwritable {
+    path: PathBuf,
+    err: io::Error,
+  },
+  InvalidManifest {
+    path: PathBuf,
+    err: serde_json::Error,
+  },
+  UnsupportedVersion {
+    name: String,
+    version: String,
+  },
+  MissingCss {
+    name: String,
+    path: PathBuf,
+  },
+  ExtendCycle {
+    names: Vec<String>,
+  },
+  InvalidName {
+    name: String,
+  },
+  AlreadyInstalled {
+    name: String,
+    path: PathBuf,
+  },
+  InvalidCss {
+    name: String,
+    err: gtk::glib::Error,
+  },
+}
+
+impl fmt::Display for ThemeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ThemeError::NotInstalled { name, path } => write!(
+        f,
+        "theme {:?} isn't installed, {} doesn't exist. `dlauncher-themes list` shows the installed themes",
+        name,
+        path.display()
+      ),
+      ThemeError::Unreadable { path, err } => write!(f, "can't read {}: {}", path.display(), err),
+      ThemeError::Unwritable { path, err } => write!(f, "can't write {}: {}", path.display(), err),
+      ThemeError::InvalidManifest { path, err } => {
+        write!(f, "{} is invalid: {}", path.display(), err)
+      }
+      ThemeError::UnsupportedVersion { name, version } => write!(
+        f,
+        "theme {:?} has manifest_version {:?}, supported versions are {}",
+        name,
+        version,
+        MANIFEST_VERSIONS.join(", ")
+      ),
+      ThemeError::MissingCss { name, path } => write!(
+        f,
+        "theme {:?} is missing its CSS file {}, check css_file in its manifest.json",
+        name,
+        path.display()
+      ),
+      ThemeError::ExtendCycle { names } => write!(
+        f,
+        "themes extend each other in a cycle: {}, remove one of their extend_theme",
+        names.join(" -> ")
+      ),
+      ThemeError::InvalidName { name } => write!(
+        f,
+        "theme name {:?} can't be used as a directory name, change name in its manifest.json",
+        name
+      ),
+      ThemeError::AlreadyInstalled { name, path } => write!(
+        f,
+        "theme {:?} is already installed in {}, use --replace to overwrite it",
+        name,
+        path.display()
+      ),
+      ThemeError::InvalidCss { name, err } => write!(f, "theme {:?} has invalid CSS: {}", name, err),
+    }
+  }
+}
+
+impl std::error::Error for ThemeError {}
+
+#[derive(Debug, Clone)]
 pub struct Theme {
   pub inner: ThemeJson,
   config: Config,
+  /// The theme's directory, usually named after the theme.
+  dir: PathBuf,
 }
 
 impl Theme {
-  pub fn new(config: Config, bytes: &[u8]) -> Self {
-    let inner = serde_json::from_slice(bytes).unwrap();
+  /// A built-in theme from its manifest, which is known to be valid.
+  fn builtin(config: Config, bytes: &[u8]) -> Self {
+    let inner: ThemeJson =
+      serde_json::from_slice(bytes).expect("a built-in theme has an invalid manifest");
+    let dir = config.themes_dir().join(&inner.name);
 
-    Self { inner, config }
+    Self { inner, config, dir }
   }
 
-  pub fn read_file(&self) -> String {
-    read_to_string(self.path().join(&self.inner.css_file)).unwrap()
+  /// Read and check the theme in the themes directory named `name`, its manifest version and CSS
+  /// file. The themes it extends are checked by `chain`.
+  pub fn load(config: &Config, name: &str) -> Result<Self, ThemeError> {
+    let dir = config.themes_dir().join(name);
+    let manifest = dir.join("manifest.json");
+    if !manifest.is_file() {
+      return Err(ThemeError::NotInstalled {
+        name: name.to_string(),
+        path: manifest,
+      });
+    }
+
+    Theme::from_dir(config, &dir)
+  }
+
+  /// Read and check the theme in `dir`.
+  pub fn from_dir(config: &Config, dir: &Path) -> Result<Self, ThemeError> {
+    let manifest = dir.join("manifest.json");
+    let bytes = read(&manifest).map_err(|err| ThemeError::Unreadable {
+      path: manifest.clone(),
+      err,
+    })?;
+    let inner: ThemeJson =
+      serde_json::from_slice(&bytes).map_err(|err| ThemeError::InvalidManifest {
+        path: manifest,
+        err,
+      })?;
+
+    if !MANIFEST_VERSIONS.contains(&inner.manifest_version.as_str()) {
+      return Err(ThemeError::UnsupportedVersion {
+        name: inner.name,
+        version: inner.manifest_version,
+      });
+    }
+
+    let theme = Self {
+      inner,
+      config: config.clone(),
+      dir: dir.to_path_buf(),
+    };
+
+    let css = theme.path().join(theme.css_file());
+    if !css.is_file() {
+      return Err(ThemeError::MissingCss {
+        name: theme.inner.name,
+        path: css,
+      });
+    }
+
+    Ok(theme)
+  }
+
+  /// Load the theme named `name` with the themes it extends, falling back to the built-in light
+  /// theme when any of them can't be used. The built-in light theme is reinstalled when the
+  /// installed copy is broken as well.
+  pub fn load_or_fallback(config: &Config, name: &str) -> Self {
+    match Theme::load(config, name).and_then(|theme| theme.chain().map(|_| theme)) {
+      Ok(theme) => theme,
+      Err(err) => {
+        error!("{}, falling back to the light theme", err);
+        Theme::fallback(config)
+      }
+    }
+  }
+
+  fn fallback(config: &Config) -> Self {
+    if let Ok(theme) = Theme::load(config, "light") {
+      if theme.inner.extend_theme.is_none() {
+        return theme;
+      }
+    }
+
+    error!("The light theme is broken, reinstalling it");
+    let reinstalled =
+      Theme::install_builtin(config, "light").and_then(|_| Theme::load(config, "light"));
+    match reinstalled {
+      Ok(theme) => theme,
+      Err(err) => {
+        // its CSS is missing, but the launcher still works unstyled
+        error!("Can't reinstall the light theme: {}", err);
+        let (_, light) = BUILTIN_THEMES[0];
+        Theme::builtin(config.clone(), light[0].1)
+      }
+    }
   }
 
   pub fn path(&self) -> PathBuf {
-    self.config.dir().join("themes").join(&self.inner.name)
+    self.dir.clone()
   }
 
   pub fn css_file(&self) -> &str {
@@ -53,33 +309,447 @@ impl Theme {
       .unwrap_or(&self.inner.css_file)
   }
 
-  pub fn compile_css(&self) -> PathBuf {
-    let css_file = self.path().join(&self.css_file());
+  /// Every theme in the themes directory that can be loaded, sorted by name.
+  pub fn installed(config: &Config) -> Vec<Theme> {
+    let mut themes = read_dir(config.themes_dir())
+      .map(|entries| {
+        entries
+          .flatten()
+          .filter_map(|entry| Theme::from_dir(config, &entry.path()).ok())
+          .collect::<Vec<Theme>>()
+      })
+      .unwrap_or_default();
+    themes.sort_by(|a, b| a.inner.name.cmp(&b.inner.name));
+
+    themes
+  }
+
+  /// Write a built-in theme to the themes directory, replacing the installed copy, along with the
+  /// built-in theme it extends. Returns false when there is no built-in theme called `name`.
+  pub fn install_builtin(config: &Config, name: &str) -> Result<bool, ThemeError> {
+    let files = match BUILTIN_THEMES.iter().find(|(builtin, _)| *builtin == name) {
+      Some((_, files)) => files,
+      None => return Ok(false),
+    };
+
+    let path = config.themes_dir().join(name);
+    create_dir_all(&path).map_err(|err| ThemeError::Unwritable {
+      path: path.clone(),
+      err,
+    })?;
+    for (file, bytes) in files.iter() {
+      let file = path.join(file);
+      write(&file, bytes).map_err(|err| ThemeError::Unwritable { path: file, err })?;
+    }
+
+    let builtin = Theme::builtin(config.clone(), files[0].1);
+    if let Some(extend_theme) = builtin.inner.extend_theme {
+      Theme::install_builtin(config, &extend_theme)?;
+    }
+
+    Ok(true)
+  }
+
+  /// Copy a Ulauncher theme into the themes directory after checking it, an installed theme with
+  /// the same name is only overwritten when `replace` is set. Returns the name of the theme, which
+  /// is also the name of its directory.
+  pub fn import(config: &Config, from: &Path, replace: bool) -> Result<String, ThemeError> {
+    let theme = Theme::from_dir(config, from)?;
+
+    // the name comes from the manifest, it must not point outside of the themes directory
+    let mut components = Path::new(&theme.inner.name).components();
+    if !matches!(
+      (components.next(), components.next()),
+      (Some(Component::Normal(_)), None)
+    ) {
+      return Err(ThemeError::InvalidName {
+        name: theme.inner.name,
+      });
+    }
+
+    let path = config.themes_dir().join(&theme.inner.name);
+    if path.exists() {
+      if !replace {
+        return Err(ThemeError::AlreadyInstalled {
+          name: theme.inner.name,
+          path,
+        });
+      }
+
+      // files the new version doesn't have anymore are removed
+      remove_dir_all(&path).map_err(|err| ThemeError::Unwritable {
+        path: path.clone(),
+        err,
+      })?;
+    }
+
+    copy_dir(from, &path)?;
+
+    Ok(theme.inner.name)
+  }
+
+  /// The themes this theme extends followed by the theme itself.
+  pub fn chain(&self) -> Result<Vec<Theme>, ThemeError> {
+    let mut chain = vec![self.clone()];
+
+    while let Some(extend_theme) = &chain[0].inner.extend_theme {
+      // themes are loaded by their directory, the name in their manifest can be different
+      let dir = self.config.themes_dir().join(extend_theme);
+      if let Some(start) = chain.iter().position(|theme| theme.dir == dir) {
+        // `chain` is in reverse, the cycle is listed from the theme that starts it
+        let mut names = chain[..=start]
+          .iter()
+          .rev()
+          .map(|theme| theme.dir_name())
+          .collect::<Vec<String>>();
+        names.push(extend_theme.clone());
+        return Err(ThemeError::ExtendCycle { names });
+      }
+
+      let extended = Theme::load(&self.config, extend_theme)?;
+      chain.insert(0, extended);
+    }
+
+    Ok(chain)
+  }
+
+  /// Name of the theme's directory, which `extend_theme` refers to.
+  fn dir_name(&self) -> String {
+    self
+      .dir
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_else(|| self.inner.name.clone())
+  }
+
+  /// CSS of the theme and the themes it extends, the CSS files are imported by their absolute path
+  /// so their own relative imports keep working, followed by the manifest variables.
+  pub fn compile_css(&self) -> Result<String, ThemeError> {
+    let chain = self.chain()?;
+
+    let imports = chain
+      .iter()
+      .map(|theme| format!("@import url({:?});\n", theme.path().join(theme.css_file())))
+      .collect::<String>();
+
+    // later themes in the chain override the variables of the themes they extend
+    let variables = chain
+      .iter()
+      .flat_map(|theme| theme.inner.variables.iter())
+      .map(|(name, value)| format!("@define-color {} {};\n", name, value))
+      .collect::<String>();
+
+    Ok(format!("{}\n{}", imports, variables))
+  }
+}
+
+/// Loads the configured theme into a single CSS provider for the whole screen and keeps it up to
+/// date: the compiled CSS is only rebuilt when a theme file changes or the preferred color scheme
+/// switches between light and dark.
+#[derive(Debug, Clone)]
+pub struct ThemeEngine {
+  config: Config,
+  provider: CssProvider,
+  /// Name of the theme that should be shown, `theme` is the light theme when it is broken.
+  name: Rc<RefCell<String>>,
+  theme: Rc<RefCell<Theme>>,
+  monitors: Rc<RefCell<Vec<FileMonitor>>>,
+  reload_pending: Rc<Cell<bool>>,
+  /// The desktop portal's `color-scheme`, `None` without a portal.
+  color_scheme: Rc<Cell<Option<u64>>>,
+}
+
+impl ThemeEngine {
+  pub fn new(config: &Config, screen: &Screen) -> Self {
+    let portal = Connection::new_session().ok();
+    let color_scheme = portal.as_ref().and_then(portal_color_scheme);
+    let name = preferred_theme(config, color_scheme);
+    let engine = Self {
+      config: config.clone(),
+      provider: CssProvider::new(),
+      theme: Rc::new(RefCell::new(Theme::load_or_fallback(config, &name))),
+      name: Rc::new(RefCell::new(name)),
+      monitors: Rc::new(RefCell::new(vec![])),
+      reload_pending: Rc::new(Cell::new(false)),
+      color_scheme: Rc::new(Cell::new(color_scheme)),
+    };
 
-    if let Some(extend_theme) = &self.inner.extend_theme {
-      let generated_css = self.path().join("generated.css");
+    gtk::StyleContext::add_provider_for_screen(
+      screen,
+      &engine.provider,
+      gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+    engine.load();
 
-      let extend_bytes = &read(
-        self
-          .config
-          .dir()
-          .join("themes")
-          .join(extend_theme)
-          .join("manifest.json"),
-      )
-      .unwrap();
-      let extend_theme: Theme = Theme::new(self.config.clone(), extend_bytes);
+    if let Some(settings) = Settings::default() {
+      let th = engine.clone();
+      settings.connect_gtk_application_prefer_dark_theme_notify(move |_| th.refresh());
+      let th = engine.clone();
+      settings.connect_gtk_theme_name_notify(move |_| th.refresh());
+    }
+    if let Some(portal) = portal {
+      engine.watch_color_scheme(portal);
+    }
+
+    engine
+  }
+
+  /// The theme that is currently loaded.
+  pub fn theme(&self) -> Theme {
+    self.theme.borrow().clone()
+  }
+
+  /// Switch to the light or dark theme when the preferred color scheme changed since the theme
+  /// was loaded.
+  pub fn refresh(&self) {
+    let preferred = preferred_theme(&self.config, self.color_scheme.get());
+
+    if preferred != *self.name.borrow() {
+      debug!("Switching to theme {}", preferred);
+      *self.name.borrow_mut() = preferred;
+      self.reload();
+    }
+  }
+
+  /// Reload the theme from disk.
+  pub fn reload(&self) {
+    let name = self.name.borrow().clone();
+    *self.theme.borrow_mut() = Theme::load_or_fallback(&self.config, &name);
+    self.load();
+  }
 
-      let st_ = format!(
-        "@import url({:?});\n\n{}",
-        extend_theme.compile_css(),
-        self.read_file(),
-      );
-      write(&generated_css, st_).unwrap();
+  fn load(&self) {
+    let theme = self.theme();
 
-      generated_css
+    let loaded = theme.compile_css().and_then(|css| {
+      self
+        .provider
+        .load_from_data(css.as_bytes())
+        .map_err(|err| ThemeError::InvalidCss {
+          name: theme.inner.name.clone(),
+          err,
+        })
+    });
+    if let Err(err) = loaded {
+      error!("{}, falling back to the light theme", err);
+
+      let fallback = Theme::fallback(&self.config);
+      if let Ok(css) = fallback.compile_css() {
+        if let Err(err) = self.provider.load_from_data(css.as_bytes()) {
+          error!("The light theme has invalid CSS: {}", err);
+        }
+      }
+      *self.theme.borrow_mut() = fallback;
+    }
+
+    self.watch();
+  }
+
+  /// Keep the portal connection on a thread that waits for `color-scheme` to change, switching the
+  /// theme live.
+  fn watch_color_scheme(&self, conn: Connection) {
+    let (sender, receiver) = MainContext::channel(PRIORITY_DEFAULT);
+    let rule = MatchRule::new_signal(PORTAL_SETTINGS, "SettingChanged");
+    let added = conn.add_match(
+      rule,
+      move |(namespace, key, value): (String, String, Variant<Box<dyn RefArg>>), _, _| {
+        if namespace == APPEARANCE && key == "color-scheme" {
+          // stops watching once the receiver is gone
+          sender.send(value.0.as_u64()).is_ok()
+        } else {
+          true
+        }
+      },
+    );
+    if let Err(err) = added {
+      error!("Can't watch the color scheme: {}", err);
+      return;
+    }
+
+    std::thread::spawn(move || loop {
+      if let Err(err) = conn.process(Duration::from_secs(60)) {
+        error!("Stopped watching the color scheme: {}", err);
+        break;
+      }
+    });
+
+    let th = self.clone();
+    receiver.attach(None, move |color_scheme| {
+      debug!("The color scheme changed to {:?}", color_scheme);
+      th.color_scheme.set(color_scheme);
+      th.refresh();
+      glib::Continue(true)
+    });
+  }
+
+  /// Watch the directories of the theme and the themes it extends, editing any of their files
+  /// reloads the theme. Only the theme's own directory is watched when it is broken, so fixing it
+  /// brings it back.
+  fn watch(&self) {
+    let mut monitors = self.monitors.borrow_mut();
+    monitors.iter().for_each(|monitor| {
+      monitor.cancel();
+    });
+    monitors.clear();
+
+    let name = self.name.borrow().clone();
+    let dirs = match Theme::load(&self.config, &name).and_then(|theme| theme.chain()) {
+      Ok(chain) => chain.iter().map(|theme| theme.path()).collect(),
+      Err(_) => vec![self.config.themes_dir().join(&name)],
+    };
+
+    for dir in dirs {
+      let monitor = match File::for_path(&dir)
+        .monitor_directory(FileMonitorFlags::NONE, gtk::gio::Cancellable::NONE)
+      {
+        Ok(monitor) => monitor,
+        Err(err) => {
+          error!("Can't watch {}: {}", dir.display(), err);
+          continue;
+        }
+      };
+
+      let th = self.clone();
+      monitor.connect_changed(move |_, _, _, _| {
+        // editors write files in several steps, wait for them to finish
+        if !th.reload_pending.replace(true) {
+          let th = th.clone();
+          timeout_add_local_once(Duration::from_millis(200), move || {
+            th.reload_pending.set(false);
+            debug!("Theme files changed, reloading");
+            th.reload();
+          });
+        }
+      });
+
+      monitors.push(monitor);
+    }
+  }
+}
+
+/// Copy a directory recursively, errors name the file that couldn't be read or written.
+fn copy_dir(from: &Path, to: &Path) -> Result<(), ThemeError> {
+  let unreadable = |path: &Path| {
+    let path = path.to_path_buf();
+    move |err| ThemeError::Unreadable { path, err }
+  };
+  let unwritable = |path: &Path| {
+    let path = path.to_path_buf();
+    move |err| ThemeError::Unwritable { path, err }
+  };
+
+  create_dir_all(to).map_err(unwritable(to))?;
+
+  for entry in read_dir(from).map_err(unreadable(from))? {
+    let entry = entry.map_err(unreadable(from))?;
+    let (source, target) = (entry.path(), to.join(entry.file_name()));
+
+    if entry.file_type().map_err(unreadable(&source))?.is_dir() {
+      copy_dir(&source, &target)?;
     } else {
-      css_file
+      let bytes = read(&source).map_err(unreadable(&source))?;
+      write(&target, bytes).map_err(unwritable(&target))?;
+    }
+  }
+
+  Ok(())
+}
+
+/// `dark_theme` when a dark color scheme is preferred and it is set, `color_theme` otherwise.
+fn preferred_theme(config: &Config, color_scheme: Option<u64>) -> String {
+  match &config.launcher.dark_theme {
+    Some(dark_theme) if prefers_dark(color_scheme) => dark_theme.clone(),
+    _ => config.launcher.color_theme.clone(),
+  }
+}
+
+/// Whether a dark color scheme is preferred, from the desktop portal's `color-scheme` setting,
+/// then GTK's `gtk-application-prefer-dark-theme` and whether the GTK theme is a dark variant.
+fn prefers_dark(color_scheme: Option<u64>) -> bool {
+  // 0 is no preference, 1 prefers dark and 2 prefers light
+  if let Some(scheme @ 1..=2) = color_scheme {
+    return scheme == 1;
+  }
+
+  Settings::default()
+    .map(|settings| {
+      settings.is_gtk_application_prefer_dark_theme()
+        || settings
+          .gtk_theme_name()
+          .map(|name| name.to_lowercase().ends_with("-dark"))
+          .unwrap_or(false)
+    })
+    .unwrap_or(false)
+}
+
+fn portal_color_scheme(conn: &Connection) -> Option<u64> {
+  let proxy = conn.with_proxy(
+    "org.freedesktop.portal.Desktop",
+    "/org/freedesktop/portal/desktop",
+    Duration::from_millis(500),
+  );
+
+  let (value,): (Variant<Box<dyn RefArg>>,) = proxy
+    .method_call(PORTAL_SETTINGS, "Read", (APPEARANCE, "color-scheme"))
+    .ok()?;
+
+  value.0.as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Once;
+
+  use super::*;
+
+  /// Point `HOME` at a temporary directory with a theme for every `(directory, extend_theme)`,
+  /// the manifest names are different from the directories.
+  fn themes(themes: &[(&str, &str)]) -> Config {
+    static HOME: Once = Once::new();
+    let home = std::env::temp_dir().join(format!("dlauncher-theme-tests-{}", std::process::id()));
+    HOME.call_once(|| std::env::set_var("HOME", &home));
+
+    let config = Config::default();
+    for (name, extend_theme) in themes {
+      let dir = config.themes_dir().join(name);
+      create_dir_all(&dir).unwrap();
+      let manifest = serde_json::json!({
+        "manifest_version": "1",
+        "name": format!("{}-manifest", name),
+        "display_name": "Test",
+        "extend_theme": extend_theme,
+        "css_file": "theme.css",
+        "matched_text_hl_colors": {
+          "when_selected": "#fff",
+          "when_not_selected": "#000",
+        },
+      });
+      write(dir.join("manifest.json"), manifest.to_string()).unwrap();
+      write(dir.join("theme.css"), "").unwrap();
     }
+
+    config
+  }
+
+  fn cycle(name: &str, config: &Config) -> Vec<String> {
+    match Theme::load(config, name).unwrap().chain() {
+      Err(ThemeError::ExtendCycle { names }) => names,
+      other => panic!("expected a cycle, got {:?}", other),
+    }
+  }
+
+  #[test]
+  fn extends_itself() {
+    let config = themes(&[("self-extend", "self-extend")]);
+    assert_eq!(
+      cycle("self-extend", &config),
+      ["self-extend", "self-extend"]
+    );
+  }
+
+  #[test]
+  fn extend_cycle() {
+    let config = themes(&[("cycle-a", "cycle-b"), ("cycle-b", "cycle-a")]);
+    assert_eq!(cycle("cycle-a", &config), ["cycle-a", "cycle-b", "cycle-a"]);
   }
 }
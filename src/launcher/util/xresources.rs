@@ -0,0 +1,22 @@
+use gtk::gdk::{property_get, Atom, Screen, ATOM_NONE};
+
+/// Value of an X resource like `Xft.dpi`, as loaded by `xrdb` into the `RESOURCE_MANAGER`
+/// property of the root window. It's read through GTK's connection to the X server, on Wayland
+/// there are no resources.
+pub fn resource(name: &str) -> Option<String> {
+  let root = Screen::default()?.root_window()?;
+  // the length is in 32 bit units
+  let (_, _, bytes) = property_get(
+    &root,
+    &Atom::intern("RESOURCE_MANAGER"),
+    &ATOM_NONE,
+    0,
+    65536,
+    0,
+  )?;
+
+  String::from_utf8_lossy(&bytes).lines().find_map(|line| {
+    let (key, value) = line.split_once(':')?;
+    (key.trim() == name).then(|| value.trim().to_string())
+  })
+}
@@ -1,30 +1,67 @@
-use std::sync::{Arc, Mutex};
+use std::{
+  cell::Cell,
+  path::PathBuf,
+  rc::Rc,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+  },
+};
 
 use gtk::{
-  gdk::{prelude::*, EventKey},
+  gdk::{prelude::*, EventKey, ModifierType, Monitor},
   prelude::*,
   Builder, Entry, EventBox, ScrolledWindow, Window as GtkWindow,
 };
-use gtk::glib::idle_add_local;
+use gtk::glib::{idle_add_local, idle_add_local_once};
 use log::{debug, error};
 
 use crate::{
-  entry::{app_entry::AppEntry, script_entry::ScriptEntry, ResultEntry},
+  entry::{
+    app_entry::AppEntry,
+    calculator_entry::CalculatorEntry,
+    character_entry::CharacterEntry,
+    clipboard_entry::ClipboardEntry,
+    file_entry::FileEntry,
+    open_with_entry::{OpenTarget, OpenWithEntry},
+    script_entry::ScriptEntry,
+    session_entry::SessionEntry,
+    shell_entry::ShellEntry,
+    window_entry::WindowEntry,
+    ResultEntry,
+  },
   extension::{Extension, ExtensionExitCode},
   fuzzy::MatchingBlocks,
   launcher::{
     navigation::Navigation,
+    preview::Preview,
     result::ResultWidget,
     util::{
       app::App,
-      config::Config,
-      display::{monitor, scaling_factor},
+      config::{Config, Placement},
+      display::{monitor, scaling_factor, IconScale},
+      keybind::Keybind,
+      layer_shell::LayerShell,
       query_history::QueryHistory,
       recent::Recent,
+      theme::ThemeEngine,
     },
   },
+  provider::{
+    calculator::Calculator,
+    characters::Characters,
+    clipboard::{ClipboardContent, ClipboardHistory},
+    files::Files,
+    session::SessionAction,
+    shell::Shell,
+    web::{quicklinks, web_search},
+    windows::{WindowInfo, Windows},
+  },
   script::Script,
-  util::{matches_app, matches_script},
+  util::{
+    copy_to_clipboard, matches_app, matches_file, matches_script, matches_session_action,
+    matches_window, no_match,
+  },
 };
 
 #[derive(Debug, Clone)]
@@ -41,12 +78,21 @@ pub struct Window {
   pub config: Config,
   /// A list of enabled extensions that are running
   pub extensions: Vec<Extension>,
+  /// The theme, loaded once for the whole screen and reloaded when its files change.
+  pub theme: ThemeEngine,
+  /// gtk-layer-shell when the window is a layer surface on Wayland.
+  pub layer_shell: Option<Arc<LayerShell>>,
+  /// Scale of the monitor the window was last shown on, result icons are loaded for it.
+  pub icon_scale: Rc<Cell<IconScale>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct WindowState {
   /// A list of desktop entries/apps that are eligible to be shown in the results.
   pub apps: Arc<Mutex<Vec<AppEntry>>>,
+  /// Apps hidden through the action menu since the launcher started, they're also saved to
+  /// `dlauncher.hidden` next to the config file.
+  pub hidden_apps: Arc<Mutex<Vec<String>>>,
   /// A list of recent apps that are shown when there is no query or to determine which result
   /// should be displayed above another.
   pub recents: Arc<Mutex<Vec<Recent>>>,
@@ -54,6 +100,27 @@ pub struct WindowState {
   pub query_history: Arc<QueryHistory>,
   /// Scripts
   pub scripts: Arc<Vec<Script>>,
+  /// Indexed files and folders, empty when file search is disabled.
+  pub files: Arc<Mutex<Vec<PathBuf>>>,
+  /// The directories files are searched in, matches are also scored by their path relative to
+  /// them.
+  pub file_roots: Arc<Vec<PathBuf>>,
+  /// Set while the files are being indexed, hiding the window again doesn't start another run.
+  pub indexing: Arc<AtomicBool>,
+  /// Completions for shell command mode.
+  pub shell: Arc<Mutex<Shell>>,
+  /// Clipboard history, only recorded in daemon mode when it is enabled. Loaded in the background
+  /// because an encrypted history waits for the keyring to be unlocked.
+  pub clipboard: Arc<Mutex<Option<ClipboardHistory>>>,
+  /// Emoji and Unicode characters for the character picker.
+  pub characters: Arc<Mutex<Characters>>,
+  /// Session actions that are available on this system, asked from logind in the background.
+  pub session_actions: Arc<Mutex<Vec<SessionAction>>>,
+  /// Open windows, listed whenever the window is shown.
+  pub windows: Arc<Mutex<Vec<WindowInfo>>>,
+  /// Set while an entry waits for a dialog to be answered, e.g. confirming a shutdown. Without
+  /// the daemon the launcher only exits once it's answered.
+  pub dialog_open: Arc<AtomicBool>,
 }
 
 #[derive(Debug, Clone)]
@@ -64,9 +131,18 @@ pub struct Result {
 
 impl Window {
   pub fn new(application: &gtk::Application, config: &Config) -> Self {
-    let apps = Arc::new(Mutex::new(App::all()));
+    let apps = Arc::new(Mutex::new(App::all(config, &[])));
     let recents = Arc::new(Mutex::new(Recent::all(&config.recents())));
     let scripts = Arc::new(Script::all(config));
+    let files = Arc::new(Mutex::new(vec![]));
+    let file_roots = Arc::new(
+      config
+        .file_search()
+        .map(|options| options.directories)
+        .unwrap_or_default(),
+    );
+    let shell = Arc::new(Mutex::new(Shell::new(config)));
+    let clipboard = Arc::new(Mutex::new(None));
     let dlauncher_str = include_str!("../../data/ui/DlauncherWindow.ui");
 
     let builder = Builder::new();
@@ -76,11 +152,22 @@ impl Window {
       .object("dlauncher_window")
       .expect("Couldn't get window");
 
+    let theme = ThemeEngine::new(config, &window.screen().unwrap());
+
     let visual = window.screen().unwrap().rgba_visual();
     if let Some(visual) = visual {
       window.set_visual(Some(&visual));
     }
 
+    let layer_shell = if config.launcher.layer_shell.unwrap_or(true) {
+      LayerShell::new().map(Arc::new)
+    } else {
+      None
+    };
+    if let Some(layer_shell) = &layer_shell {
+      layer_shell.init(&window);
+    }
+
     window.set_application(Some(application));
 
     let query_history = Arc::new(QueryHistory::new(config.clone()));
@@ -88,56 +175,72 @@ impl Window {
     let mut sel = Self {
       state: WindowState {
         apps,
+        hidden_apps: Arc::new(Mutex::new(vec![])),
         scripts,
         recents,
         query_history: query_history.clone(),
+        files,
+        file_roots,
+        indexing: Arc::new(AtomicBool::new(false)),
+        shell,
+        clipboard,
+        characters: Arc::new(Mutex::new(Characters::new(config))),
+        session_actions: Arc::new(Mutex::new(vec![])),
+        windows: Arc::new(Mutex::new(vec![])),
+        dialog_open: Arc::new(AtomicBool::new(false)),
       },
       builder,
       navigation: Arc::new(Mutex::new(Navigation::new(query_history))),
       window,
       config: config.clone(),
       extensions: vec![],
+      theme,
+      layer_shell,
+      icon_scale: Rc::new(Cell::new(IconScale::default())),
     };
 
     sel.extensions = sel.config.extensions(&sel);
+    sel.index_files();
+    sel.find_session_actions();
+
+    if sel.config.main.daemon && sel.config.clipboard_history().is_some() {
+      ClipboardHistory::load_in_background(&sel.config, sel.state.clipboard.clone());
+      ClipboardHistory::watch(sel.state.clipboard.clone());
+    }
 
     sel
   }
 
-  fn styles(&self) {
-    let provider = gtk::CssProvider::new();
-    provider
-      .load_from_path(
-        self
-          .config
-          .theme()
-          .compile_css()
-          .as_os_str()
-          .to_str()
-          .unwrap(),
-      )
-      .unwrap();
-
-    gtk::StyleContext::add_provider_for_screen(
-      &self.window.screen().unwrap(),
-      &provider,
-      gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-    );
+  /// Rebuild the file search index in the background.
+  fn index_files(&self) {
+    if let Some(options) = self.config.file_search() {
+      if self.state.indexing.swap(true, Ordering::SeqCst) {
+        debug!("Files are already being indexed");
+        return;
+      }
 
-    gtk::StyleContext::add_provider(
-      &self.window.style_context(),
-      &provider,
-      gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-    );
+      let files = self.state.files.clone();
+      let indexing = self.state.indexing.clone();
 
-    for child in self.window.children() {
-      gtk::StyleContext::add_provider(
-        &child.style_context(),
-        &provider,
-        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-      );
+      std::thread::spawn(move || {
+        let indexed = Files::index(&options);
+        *files.lock().unwrap() = indexed;
+        indexing.store(false, Ordering::SeqCst);
+      });
+    }
+  }
+
+  fn find_session_actions(&self) {
+    if let Some(options) = self.config.session() {
+      let session_actions = self.state.session_actions.clone();
+
+      std::thread::spawn(move || {
+        *session_actions.lock().unwrap() = SessionAction::available(&options);
+      });
     }
+  }
 
+  fn styles(&self) {
     if let Some(visual) = self.window.visual() {
       self.window.set_visual(Some(&visual));
     }
@@ -148,11 +251,16 @@ impl Window {
     self.window.set_size_request(width + 2, height);
   }
 
-  fn position_window(&self) {
-    let monitor = monitor();
+  fn position_window(&self, monitor: &Monitor) {
+    let placement = self.config.window();
     let geo = monitor.geometry();
-    let max_height = geo.height() as f32 - (geo.height() as f32 * 0.15) - 100.0;
-    let window_width = 500.0 * scaling_factor() + 100.0;
+    let max_height = geo.height() as f32 - (geo.height() as f32 * placement.bottom_margin) - 100.0;
+    let scale = scaling_factor(monitor, placement.scaling_factor);
+    let window_width = match placement.width {
+      Some(width) => width as f32,
+      None => 500.0 * scale + 100.0,
+    };
+    self.icon_scale.set(IconScale::new(monitor, scale));
 
     self
       .window
@@ -161,17 +269,40 @@ impl Window {
       self.builder.object("result_box_scroll_container").unwrap();
     result_box_scroll_container.set_property("max-content-height", max_height as i32);
 
+    // layer surfaces are centered by the compositor, only the output and the distance from the top
+    // are set
+    if let Some(layer_shell) = &self.layer_shell {
+      let output = match placement.placement {
+        Placement::Focused => None,
+        _ => Some(monitor),
+      };
+      layer_shell.set_monitor(&self.window, output);
+
+      let margin = geo.height() as f32 * placement.offset + 92.0;
+      layer_shell.set_top_margin(&self.window, margin as i32);
+      return;
+    }
+
     let x = geo.width() as f32 * 0.5 - window_width * 0.5 + geo.x() as f32;
-    let y = geo.y() as f32 + geo.height() as f32 * 0.12;
+    let y = geo.y() as f32 + geo.height() as f32 * placement.offset;
 
     self.window.move_(x as i32, (y + 92_f32) as i32);
   }
 
   /// Show the GTK window, and refresh the apps and recents.
   pub fn show_window(&self) {
+    self.navigation.lock().unwrap().parent = None;
+
+    if self.config.window_switcher().is_some() {
+      *self.state.windows.lock().unwrap() = Windows::list();
+    }
+
+    // before presenting, the focused window is still the one the launcher was opened from
+    let monitor = monitor(&self.config.window().placement);
+
     self.styles();
     self.window.present();
-    self.position_window();
+    self.position_window(&monitor);
     self.fix_window_width();
 
     self.show_results(vec![], false);
@@ -186,15 +317,18 @@ impl Window {
 
   pub fn hide_window(&self) {
     self.window.hide();
+    self.index_files();
 
     let state = self.state.clone();
+    let config = self.config.clone();
     let config_recents = self.config.recents();
 
     idle_add_local(move || {
       let mut apps = state.apps.lock().unwrap();
       let mut recents = state.recents.lock().unwrap();
-      *apps = App::all();
+      *apps = App::all(&config, &state.hidden_apps.lock().unwrap());
       *recents = Recent::all(&config_recents);
+      state.shell.lock().unwrap().refresh(&config);
 
       // For some reason the mutex doesn't go out of scope and get automatically dropped so i had to do this.
       drop(apps);
@@ -204,6 +338,16 @@ impl Window {
     });
   }
 
+  /// Exit after an entry was run without the daemon. While the entry still waits for a dialog
+  /// the window is only hidden, the dialog exits once it's answered.
+  pub fn quit(&self) {
+    if self.state.dialog_open.load(Ordering::SeqCst) {
+      self.window.hide();
+    } else {
+      std::process::exit(0);
+    }
+  }
+
   /// Add a result widget to the results box
   ///
   /// Useful for extensions that don't want to clear the entire result box, but just want to add a
@@ -232,29 +376,50 @@ impl Window {
 
     if override_ && results.is_empty() {
       scroll_box.hide();
+      self.show_preview(None);
       return;
     }
 
     let mut results = if results.is_empty() {
+      let pinned = self
+        .config
+        .apps()
+        .pinned
+        .unwrap_or_default()
+        .into_iter()
+        .map(PathBuf::from)
+        .collect::<Vec<PathBuf>>();
+
       let mut res = self
         .state
         .recents
         .lock()
         .unwrap()
         .iter()
+        .filter(|recent| !pinned.contains(&recent.file))
         .map(|recent| recent.to_result(self.clone(), self.state.apps.clone()))
         .filter(|result| result.is_some())
         .flatten()
         .collect::<Vec<ResultWidget>>();
 
-      if res.is_empty() {
-        scroll_box.hide();
-      }
-
       if res.len() > self.config.launcher.frequent_apps as usize {
         res.truncate(self.config.launcher.frequent_apps as usize);
       }
 
+      // pinned apps come first and don't count towards `frequent_apps`
+      let apps = self.state.apps.lock().unwrap();
+      let pinned = pinned
+        .iter()
+        .filter_map(|file| apps.iter().find(|app| &app.file == file))
+        .map(|app| ResultWidget::new(ResultEntry::App(app.clone()), self.clone(), no_match()));
+      res.splice(0..0, pinned);
+      drop(apps);
+
+      if res.is_empty() {
+        scroll_box.hide();
+        self.show_preview(None);
+      }
+
       res
     } else {
       results
@@ -273,6 +438,19 @@ impl Window {
     results.iter().for_each(|r| r.setup());
   }
 
+  /// Show the preview of the selected result next to the results, `None` hides the pane.
+  pub fn show_preview(&self, preview: Option<Preview>) {
+    let pane: gtk::Box = self.builder.object("preview").unwrap();
+
+    match preview {
+      Some(preview) => {
+        preview.render(&self.builder);
+        pane.show();
+      }
+      None => pane.hide(),
+    }
+  }
+
   fn add_one_to_results(&self, result: &ResultWidget) {
     let result_box: gtk::Box = self
       .builder
@@ -309,75 +487,153 @@ impl Window {
       result_box.set_margin_top(3);
       result_box.set_margin_bottom(10);
 
-      let provider = gtk::CssProvider::new();
-      provider
-        .load_from_path(
-          self
-            .config
-            .theme()
-            .compile_css()
-            .as_os_str()
-            .to_str()
-            .unwrap(),
-        )
-        .unwrap();
+      let scroll_box: ScrolledWindow = self.builder.object("result_box_scroll_container").unwrap();
 
-      gtk::StyleContext::add_provider(
-        &result_box.style_context(),
-        &provider,
-        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-      );
+      scroll_box.show_all();
+    }
+  }
 
-      for child in result_box.children() {
-        gtk::StyleContext::add_provider(
-          &child.style_context(),
-          &provider,
-          gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-        );
+  /// Show the actions of the selected result in place of the results, they're navigated and
+  /// activated like results.
+  pub fn open_action_menu(&self) {
+    let (entry, parent) = {
+      let navigation = self.navigation.lock().unwrap();
+      match navigation.selected {
+        Some(selected) if navigation.parent.is_none() => (
+          navigation.results[selected as usize].entry.clone(),
+          (navigation.results.clone(), selected),
+        ),
+        _ => return,
       }
+    };
 
-      let scroll_box: ScrolledWindow = self.builder.object("result_box_scroll_container").unwrap();
+    let actions = entry.actions(&self.config);
+    if actions.is_empty() {
+      return;
+    }
 
-      scroll_box.show_all();
+    let results = actions
+      .into_iter()
+      .map(|action| ResultWidget::new(ResultEntry::Action(action), self.clone(), no_match()))
+      .collect();
+    self.show_results(results, true);
+    self.navigation.lock().unwrap().parent = Some(parent);
+  }
+
+  /// Go back from the action menu to the results it was opened from, returns false when the action
+  /// menu isn't open.
+  pub fn close_action_menu(&self) -> bool {
+    let parent = self.navigation.lock().unwrap().parent.take();
+
+    match parent {
+      Some((results, selected)) => {
+        // new widgets, the old ones already have their signal handlers connected
+        let results = results
+          .into_iter()
+          .map(|result| ResultWidget::new(result.entry, self.clone(), result.match_))
+          .collect();
+        self.show_results(results, true);
+        self.navigation.lock().unwrap().select(selected);
+        true
+      }
+      None => false,
     }
   }
 
+  /// Search for the current query again after something changed what it matches, e.g. an app
+  /// that was hidden. Deferred so actions can call it while navigation is locked.
+  pub fn refresh_results(&self) {
+    let window = self.clone();
+    idle_add_local_once(move || {
+      let input: Entry = window.builder.object("input").expect("Couldn't get input");
+      window.connect_changed(&input);
+    });
+  }
+
   fn connect_key_press_event(&self, key: &EventKey) -> Inhibit {
+    let custom = self.config.keybinds();
+    let pressed = Keybind::from_event(key);
+    let is = |keybinds: &[Keybind]| keybinds.iter().any(|keybind| keybind.matches(&pressed));
+    let is_open = custom
+      .open
+      .iter()
+      .any(|keybind| keybind.matches_loosely(&pressed));
+
+    // handled before locking navigation, opening and closing the menu replaces the results
+    let alt = key.state().contains(ModifierType::MOD1_MASK);
+    if is(&custom.action_menu) || (alt && is_open) {
+      if !self.close_action_menu() {
+        self.open_action_menu();
+      }
+      // keeps Tab from moving the focus out of the input
+      return Inhibit(true);
+    } else if is(&custom.close) && self.close_action_menu() {
+      return Inhibit(true);
+    } else if is(&custom.delete_word) {
+      // changing the text searches again, which locks navigation
+      let input: Entry = self.builder.object("input").expect("Couldn't get input");
+      delete_word(&input);
+      return Inhibit(true);
+    }
+
+    // Alt+1 to Alt+9 open the result with that number
+    let shortcut = key
+      .keyval()
+      .to_unicode()
+      .and_then(|c| c.to_digit(10))
+      .filter(|digit| alt && *digit > 0);
+
     let mut navigation = self.navigation.lock().unwrap();
     let input: Entry = self.builder.object("input").expect("Couldn't get input");
 
-    if let Some(keycode) = key.keyval().name() {
-      let keycode = keycode.to_string();
-      let custom = self.config.keybinds();
-
-      if keycode == custom.result_up {
-        navigation.go_up();
-      } else if keycode == custom.result_down {
-        navigation.go_down();
-      } else if keycode == custom.open {
-        if let Some(selected) = navigation.selected {
-          let entry = &navigation.results[selected as usize].entry;
-          if !input.text().is_empty() {
-            self
-              .state
-              .query_history
-              .save_query(input.text(), entry.name());
-            debug!("Saved query_history {}: {}", input.text(), entry.name());
-          }
+    if let Some(digit) = shortcut {
+      if digit as usize > navigation.results.len() {
+        return Inhibit(true);
+      }
+      navigation.select(digit as u16 - 1);
+    }
 
-          if self.config.main.daemon {
-            self.hide_window();
-            entry.execute(self.clone());
-          } else {
-            entry.execute(self.clone());
-            std::process::exit(0);
-          }
+    if is(&custom.result_up) {
+      navigation.go_up();
+    } else if is(&custom.result_down) {
+      navigation.go_down();
+    } else if is(&custom.page_up) {
+      navigation.page_up();
+    } else if is(&custom.page_down) {
+      navigation.page_down();
+    } else if is(&custom.first) {
+      navigation.first();
+    } else if is(&custom.last) {
+      navigation.last();
+    } else if is(&custom.copy_name) {
+      if let Some(selected) = navigation.selected {
+        copy_to_clipboard(navigation.results[selected as usize].entry.name());
+      }
+      return Inhibit(true);
+    } else if is(&custom.close) {
+      if self.config.main.daemon {
+        self.hide_window();
+      } else {
+        std::process::exit(0);
+      }
+    } else if is_open || shortcut.is_some() {
+      if let Some(selected) = navigation.selected {
+        let entry = &navigation.results[selected as usize].entry;
+        // actions aren't remembered as the result picked for the query
+        if !input.text().is_empty() && navigation.parent.is_none() {
+          self
+            .state
+            .query_history
+            .save_query(input.text(), entry.name());
+          debug!("Saved query_history {}: {}", input.text(), entry.name());
         }
-      } else if keycode == custom.close {
+
         if self.config.main.daemon {
           self.hide_window();
+          entry.execute_with(self.clone(), key.state());
         } else {
-          std::process::exit(0);
+          entry.execute_with(self.clone(), key.state());
+          self.quit();
         }
       }
     }
@@ -387,14 +643,56 @@ impl Window {
   }
 
   fn connect_changed(&self, input: &Entry) {
+    self.navigation.lock().unwrap().parent = None;
+
     let text = input.text();
     let text = text.trim_start();
     input.set_text(text);
 
     let mut results = Vec::new();
 
+    let shell_mode = self.config.shell_mode();
+    let shell_command = shell_mode
+      .as_ref()
+      .and_then(|shell_mode| text.strip_prefix(&shell_mode.prefix));
+
+    let character_picker = self.config.characters();
+    let character_query = character_picker
+      .as_ref()
+      .and_then(|picker| text.strip_prefix(&picker.prefix));
+
+    let clipboard_query = self.config.clipboard_history().and_then(|options| {
+      text
+        .strip_prefix(&options.keyword)
+        .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        .map(str::trim)
+    });
+
     if text.is_empty() {
       self.show_results(vec![], false);
+    } else if let (Some(shell_mode), Some(command)) = (&shell_mode, shell_command) {
+      self.show_results(self.shell_results(&shell_mode.shell, command.trim()), true);
+    } else if let (Some(picker), Some(query)) = (&character_picker, character_query) {
+      let characters = self.state.characters.lock().unwrap().search(
+        query,
+        picker,
+        self.config.main.least_score,
+        9,
+      );
+
+      let results = characters
+        .into_iter()
+        .map(|character| {
+          ResultWidget::new(
+            ResultEntry::Character(CharacterEntry::new(character)),
+            self.clone(),
+            no_match(),
+          )
+        })
+        .collect();
+      self.show_results(results, true);
+    } else if let Some(results) = clipboard_query.and_then(|query| self.clipboard_results(query)) {
+      self.show_results(results, true);
     } else {
       let mut unsort = Vec::new();
       let apps = self.state.apps.lock().unwrap();
@@ -404,6 +702,32 @@ impl Window {
         }
       }
 
+      // `app argument` queries, e.g. `firefox example.com` or `visual studio code ~/project`. App
+      // names can have spaces, each app uses the longest prefix of the query that matches it.
+      let splits = text
+        .match_indices(' ')
+        .rev()
+        .filter_map(|(i, _)| Some((&text[..i], OpenTarget::parse(&text[i + 1..])?)))
+        .collect::<Vec<(&str, OpenTarget)>>();
+      for app in apps.iter() {
+        let open_with = splits
+          .iter()
+          .filter(|(_, target)| target.supported_by(app))
+          .find_map(|(app_query, target)| {
+            matches_app(app, app_query, self.config.main.least_score)
+              .map(|(match_, score)| (target, match_, score))
+          });
+
+        if let Some((target, match_, score)) = open_with {
+          unsort.push((
+            ResultEntry::OpenWith(OpenWithEntry::new(app.clone(), target.clone())),
+            self.clone(),
+            match_,
+            score,
+          ));
+        }
+      }
+
       for script in self.state.scripts.iter() {
         if let Some((match_, score)) = matches_script(script, text, self.config.main.least_score) {
           unsort.push((
@@ -415,6 +739,48 @@ impl Window {
         }
       }
 
+      if let Some(options) = self.config.session() {
+        for action in self.state.session_actions.lock().unwrap().iter() {
+          if let Some((match_, score)) =
+            matches_session_action(action, text, self.config.main.least_score)
+          {
+            unsort.push((
+              ResultEntry::Session(SessionEntry::new(*action, options.clone())),
+              self.clone(),
+              match_,
+              score,
+            ));
+          }
+        }
+      }
+
+      for window in self.state.windows.lock().unwrap().iter() {
+        if let Some((match_, score)) = matches_window(window, text, self.config.main.least_score) {
+          unsort.push((
+            ResultEntry::Window(WindowEntry::new(window.clone(), &apps)),
+            self.clone(),
+            match_,
+            score,
+          ));
+        }
+      }
+
+      for file in self.state.files.lock().unwrap().iter() {
+        if let Some((name, match_, score)) = matches_file(
+          file,
+          &self.state.file_roots,
+          text,
+          self.config.main.least_score,
+        ) {
+          unsort.push((
+            ResultEntry::File(FileEntry::new(file.clone()).with_name(name)),
+            self.clone(),
+            match_,
+            score,
+          ));
+        }
+      }
+
       let mut unsort: Vec<(ResultEntry, Window, MatchingBlocks, usize)> = unsort
         .into_iter()
         .filter(|x| x.3 > x.1.config.main.least_score)
@@ -428,6 +794,34 @@ impl Window {
           .map(|(entry, window, match_, _)| ResultWidget::new(entry, window, match_)),
       );
 
+      if let Some(calculation) = Calculator::evaluate(text) {
+        results.insert(
+          0,
+          ResultWidget::new(
+            ResultEntry::Calculator(CalculatorEntry::new(calculation)),
+            self.clone(),
+            no_match(),
+          ),
+        );
+      }
+
+      // quicklinks go first, typing their keyword should always open them
+      let links = quicklinks(&self.config.quicklinks(), text, self.config.main.least_score);
+      for (i, link) in links.into_iter().enumerate() {
+        let result = ResultWidget::new(ResultEntry::Web(link), self.clone(), no_match());
+        results.insert(i, result);
+      }
+
+      if results.is_empty() {
+        if let Some(search) = self.config.web_search() {
+          results.push(ResultWidget::new(
+            ResultEntry::Web(web_search(&search, text)),
+            self.clone(),
+            no_match(),
+          ));
+        }
+      }
+
       if results.len() > 9 {
         results.truncate(9);
       }
@@ -445,6 +839,58 @@ impl Window {
     }
   }
 
+  /// Results for shell command mode, the command itself followed by completions from `$PATH` and
+  /// history.
+  fn shell_results(&self, shell: &str, command: &str) -> Vec<ResultWidget> {
+    let mut commands = vec![];
+    if !command.is_empty() {
+      commands.push(command.to_string());
+    }
+    commands.extend(self.state.shell.lock().unwrap().complete(command, 8));
+
+    commands
+      .into_iter()
+      .map(|command| {
+        ResultWidget::new(
+          ResultEntry::Shell(ShellEntry::new(command, shell)),
+          self.clone(),
+          no_match(),
+        )
+      })
+      .collect()
+  }
+
+  /// Results for the clipboard history keyword, the most recently copied items matching `query`.
+  /// `None` while the history isn't loaded.
+  fn clipboard_results(&self, query: &str) -> Option<Vec<ResultWidget>> {
+    let clipboard = self.state.clipboard.lock().unwrap();
+    let clipboard = clipboard.as_ref()?;
+
+    let results = clipboard
+      .search(query, self.config.main.least_score)
+      .into_iter()
+      .take(9)
+      .map(|item| {
+        let image = match &item.content {
+          ClipboardContent::Image { file, .. } => clipboard.load_image(file),
+          ClipboardContent::Text(_) => None,
+        };
+
+        ResultWidget::new(
+          ResultEntry::Clipboard(ClipboardEntry::new(
+            item,
+            image,
+            clipboard.options.paste_command.clone(),
+          )),
+          self.clone(),
+          no_match(),
+        )
+      })
+      .collect();
+
+    Some(results)
+  }
+
   pub fn build_ui(&self) {
     if !self.config.main.daemon {
       self.show_window();
@@ -471,3 +917,21 @@ impl Window {
     input.connect_changed(move |entry| th.connect_changed(entry));
   }
 }
+
+/// Delete the word before the cursor along with the whitespace after it, like Ctrl+W in a shell.
+fn delete_word(input: &Entry) {
+  let end = input.position();
+  let text = input.text();
+  let before = text.chars().take(end as usize).collect::<Vec<char>>();
+
+  let mut start = before.len();
+  while start > 0 && before[start - 1].is_whitespace() {
+    start -= 1;
+  }
+  while start > 0 && !before[start - 1].is_whitespace() {
+    start -= 1;
+  }
+
+  input.delete_text(start as i32, end);
+  input.set_position(start as i32);
+}
@@ -12,4 +12,7 @@ pub mod util;
 /// Fuzzy Search utilities
 pub mod fuzzy;
 
+/// Built-in result providers
+pub mod provider;
+
 pub mod script;
@@ -0,0 +1,718 @@
+use std::f64::consts;
+
+/// A successfully evaluated query.
+#[derive(Debug, Clone)]
+pub struct Calculation {
+  /// The query that was evaluated, normalized for display.
+  pub expression: String,
+  /// The formatted result, this is what gets copied.
+  pub value: String,
+}
+
+pub struct Calculator;
+
+impl Calculator {
+  /// Evaluate a query as an arithmetic expression (`2+2*3`, `sqrt(2)`, `0xff + 1`, `200 + 10%`), a
+  /// base conversion (`255 in hex`) or a unit conversion (`10 km in mi`, `30 c to f`).
+  ///
+  /// Returns `None` when the query doesn't look like something to calculate, plain numbers and
+  /// queries without any digits are ignored so normal searches aren't interrupted.
+  pub fn evaluate(query: &str) -> Option<Calculation> {
+    let query = query.trim();
+    if !query.chars().any(|c| c.is_ascii_digit()) {
+      return None;
+    }
+
+    if let Some((lhs, target)) = split_conversion(query) {
+      if let Some(base) = Base::parse(target) {
+        let value = Parser::evaluate(lhs)?.value;
+        return Some(Calculation {
+          expression: format!("{} in {}", lhs, target),
+          value: base.format(value)?,
+        });
+      }
+
+      if let Some(to) = Unit::find(target) {
+        let (value, from) = split_unit(lhs)?;
+        let converted = from.convert(value, &to)?;
+
+        return Some(Calculation {
+          expression: format!("{} {} in {}", format_number(value)?, from.symbol, to.symbol),
+          value: format!("{} {}", format_number(converted)?, to.symbol),
+        });
+      }
+    }
+
+    let evaluated = Parser::evaluate(query)?;
+    if !evaluated.nontrivial {
+      return None;
+    }
+
+    Some(Calculation {
+      expression: query.to_string(),
+      value: format_number(evaluated.value)?,
+    })
+  }
+}
+
+/// Split `lhs in target` (also `to` and `as`) on the last keyword, so `1 in in cm` works.
+fn split_conversion(query: &str) -> Option<(&str, &str)> {
+  let (i, len) = query.char_indices().rev().find_map(|(i, _)| {
+    [" in ", " to ", " as "]
+      .iter()
+      .find(|keyword| {
+        query
+          .get(i..i + keyword.len())
+          .map(|s| s.eq_ignore_ascii_case(keyword))
+          .unwrap_or(false)
+      })
+      .map(|keyword| (i, keyword.len()))
+  })?;
+
+  let (lhs, target) = (query[..i].trim(), query[i + len..].trim());
+  if lhs.is_empty() || target.is_empty() {
+    None
+  } else {
+    Some((lhs, target))
+  }
+}
+
+/// Split `10 km`/`10km` into the evaluated amount and its unit, preferring the longest unit.
+fn split_unit(lhs: &str) -> Option<(f64, Unit)> {
+  lhs.char_indices().skip(1).find_map(|(i, _)| {
+    let unit = Unit::find(lhs[i..].trim())?;
+    let value = Parser::evaluate(&lhs[..i])?.value;
+    Some((value, unit))
+  })
+}
+
+/// Format a number with at most 10 decimals, large and tiny numbers use scientific notation.
+pub fn format_number(value: f64) -> Option<String> {
+  if !value.is_finite() {
+    return None;
+  }
+
+  let abs = value.abs();
+  if abs >= 1e15 || (abs < 1e-10 && abs != 0.0) {
+    return Some(format!("{:e}", value));
+  }
+
+  let formatted = format!("{:.10}", value);
+  let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+
+  Some(if formatted == "-0" {
+    "0".to_string()
+  } else {
+    formatted.to_string()
+  })
+}
+
+enum Base {
+  Hex,
+  Binary,
+  Octal,
+  Decimal,
+}
+
+impl Base {
+  fn parse(name: &str) -> Option<Self> {
+    match name.to_lowercase().as_str() {
+      "hex" | "hexadecimal" => Some(Base::Hex),
+      "bin" | "binary" => Some(Base::Binary),
+      "oct" | "octal" => Some(Base::Octal),
+      "dec" | "decimal" => Some(Base::Decimal),
+      _ => None,
+    }
+  }
+
+  fn format(&self, value: f64) -> Option<String> {
+    // `u64::MAX as f64` rounds up to 2^64, so anything below it fits without saturating
+    if value.fract() != 0.0 || value.abs() >= u64::MAX as f64 {
+      return match self {
+        Base::Decimal => format_number(value),
+        _ => None,
+      };
+    }
+
+    let sign = if value < 0.0 { "-" } else { "" };
+    let abs = value.abs() as u64;
+
+    Some(match self {
+      Base::Hex => format!("{}0x{:X}", sign, abs),
+      Base::Binary => format!("{}0b{:b}", sign, abs),
+      Base::Octal => format!("{}0o{:o}", sign, abs),
+      Base::Decimal => format!("{}{}", sign, abs),
+    })
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dimension {
+  Length,
+  Mass,
+  Time,
+  Data,
+  Volume,
+  Speed,
+  Area,
+  Temperature,
+}
+
+#[derive(Debug, Clone)]
+struct Unit {
+  symbol: &'static str,
+  dimension: Dimension,
+  /// Multiplier to the dimension's base unit, for temperatures this is unused.
+  factor: f64,
+}
+
+// (names, symbol shown in results, dimension, factor to the base unit)
+#[rustfmt::skip]
+const UNITS: &[(&[&str], &str, Dimension, f64)] = &[
+  (&["m", "meter", "meters", "metre", "metres"], "m", Dimension::Length, 1.0),
+  (&["km", "kilometer", "kilometers", "kilometre", "kilometres"], "km", Dimension::Length, 1000.0),
+  (&["cm", "centimeter", "centimeters", "centimetre", "centimetres"], "cm", Dimension::Length, 0.01),
+  (&["mm", "millimeter", "millimeters", "millimetre", "millimetres"], "mm", Dimension::Length, 0.001),
+  (&["um", "µm", "micrometer", "micrometers"], "µm", Dimension::Length, 1e-6),
+  (&["nm", "nanometer", "nanometers"], "nm", Dimension::Length, 1e-9),
+  (&["mi", "mile", "miles"], "mi", Dimension::Length, 1609.344),
+  (&["yd", "yard", "yards"], "yd", Dimension::Length, 0.9144),
+  (&["ft", "foot", "feet"], "ft", Dimension::Length, 0.3048),
+  (&["in", "inch", "inches"], "in", Dimension::Length, 0.0254),
+  (&["nmi", "nautical mile", "nautical miles"], "nmi", Dimension::Length, 1852.0),
+  (&["kg", "kilogram", "kilograms", "kilo", "kilos"], "kg", Dimension::Mass, 1.0),
+  (&["g", "gram", "grams"], "g", Dimension::Mass, 0.001),
+  (&["mg", "milligram", "milligrams"], "mg", Dimension::Mass, 1e-6),
+  (&["t", "tonne", "tonnes", "ton", "tons"], "t", Dimension::Mass, 1000.0),
+  (&["lb", "lbs", "pound", "pounds"], "lb", Dimension::Mass, 0.45359237),
+  (&["oz", "ounce", "ounces"], "oz", Dimension::Mass, 0.028349523125),
+  (&["st", "stone", "stones"], "st", Dimension::Mass, 6.35029318),
+  (&["s", "sec", "secs", "second", "seconds"], "s", Dimension::Time, 1.0),
+  (&["ms", "millisecond", "milliseconds"], "ms", Dimension::Time, 0.001),
+  (&["min", "mins", "minute", "minutes"], "min", Dimension::Time, 60.0),
+  (&["h", "hr", "hrs", "hour", "hours"], "h", Dimension::Time, 3600.0),
+  (&["d", "day", "days"], "d", Dimension::Time, 86400.0),
+  (&["wk", "week", "weeks"], "wk", Dimension::Time, 604800.0),
+  (&["yr", "year", "years"], "yr", Dimension::Time, 31557600.0),
+  (&["B", "byte", "bytes"], "B", Dimension::Data, 1.0),
+  (&["bit", "bits"], "bit", Dimension::Data, 0.125),
+  (&["KB", "kB", "kilobyte", "kilobytes"], "kB", Dimension::Data, 1e3),
+  (&["MB", "megabyte", "megabytes"], "MB", Dimension::Data, 1e6),
+  (&["GB", "gigabyte", "gigabytes"], "GB", Dimension::Data, 1e9),
+  (&["TB", "terabyte", "terabytes"], "TB", Dimension::Data, 1e12),
+  (&["KiB", "kibibyte", "kibibytes"], "KiB", Dimension::Data, 1024.0),
+  (&["MiB", "mebibyte", "mebibytes"], "MiB", Dimension::Data, 1048576.0),
+  (&["GiB", "gibibyte", "gibibytes"], "GiB", Dimension::Data, 1073741824.0),
+  (&["TiB", "tebibyte", "tebibytes"], "TiB", Dimension::Data, 1099511627776.0),
+  (&["l", "L", "liter", "liters", "litre", "litres"], "L", Dimension::Volume, 1.0),
+  (&["ml", "mL", "milliliter", "milliliters", "millilitre", "millilitres"], "mL", Dimension::Volume, 0.001),
+  (&["m3", "m³", "cubic meter", "cubic meters"], "m³", Dimension::Volume, 1000.0),
+  (&["gal", "gallon", "gallons"], "gal", Dimension::Volume, 3.785411784),
+  (&["qt", "quart", "quarts"], "qt", Dimension::Volume, 0.946352946),
+  (&["pt", "pint", "pints"], "pt", Dimension::Volume, 0.473176473),
+  (&["cup", "cups"], "cup", Dimension::Volume, 0.2365882365),
+  (&["floz", "fl oz", "fluid ounce", "fluid ounces"], "fl oz", Dimension::Volume, 0.0295735295625),
+  (&["m/s", "mps"], "m/s", Dimension::Speed, 1.0),
+  (&["km/h", "kmh", "kph"], "km/h", Dimension::Speed, 1.0 / 3.6),
+  (&["mph", "mi/h"], "mph", Dimension::Speed, 0.44704),
+  (&["kn", "knot", "knots"], "kn", Dimension::Speed, 1852.0 / 3600.0),
+  (&["m2", "m²", "square meter", "square meters"], "m²", Dimension::Area, 1.0),
+  (&["km2", "km²", "square kilometer", "square kilometers"], "km²", Dimension::Area, 1e6),
+  (&["ft2", "ft²", "sqft", "square foot", "square feet"], "ft²", Dimension::Area, 0.09290304),
+  (&["ha", "hectare", "hectares"], "ha", Dimension::Area, 1e4),
+  (&["acre", "acres"], "acre", Dimension::Area, 4046.8564224),
+  (&["c", "°c", "celsius"], "°C", Dimension::Temperature, 0.0),
+  (&["f", "°f", "fahrenheit"], "°F", Dimension::Temperature, 0.0),
+  (&["k", "kelvin"], "K", Dimension::Temperature, 0.0),
+];
+
+impl Unit {
+  /// Look up a unit by name, exact matches win so `MB` and `mb` or `m` and `M` can be told apart
+  /// when both exist.
+  fn find(name: &str) -> Option<Self> {
+    let lower = name.to_lowercase();
+
+    UNITS
+      .iter()
+      .find(|(names, ..)| names.contains(&name))
+      .or_else(|| {
+        UNITS
+          .iter()
+          .find(|(names, ..)| names.iter().any(|n| n.to_lowercase() == lower))
+      })
+      .map(|&(_, symbol, dimension, factor)| Unit {
+        symbol,
+        dimension,
+        factor,
+      })
+  }
+
+  fn convert(&self, value: f64, to: &Unit) -> Option<f64> {
+    if self.dimension != to.dimension {
+      return None;
+    }
+
+    if self.dimension == Dimension::Temperature {
+      let kelvin = match self.symbol {
+        "°C" => value + 273.15,
+        "°F" => (value - 32.0) * 5.0 / 9.0 + 273.15,
+        _ => value,
+      };
+
+      return Some(match to.symbol {
+        "°C" => kelvin - 273.15,
+        "°F" => (kelvin - 273.15) * 9.0 / 5.0 + 32.0,
+        _ => kelvin,
+      });
+    }
+
+    Some(value * self.factor / to.factor)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  Number(f64),
+  Ident(String),
+  Op(char),
+  LParen,
+  RParen,
+  Comma,
+}
+
+/// Split the input into tokens, also returns whether a hex, binary or octal literal was used.
+fn tokenize(input: &str) -> Option<(Vec<Token>, bool)> {
+  let chars = input.chars().collect::<Vec<char>>();
+  let mut tokens = Vec::new();
+  let mut radix_literal = false;
+  let mut i = 0;
+
+  while i < chars.len() {
+    let c = chars[i];
+
+    if c.is_whitespace() || c == '_' {
+      i += 1;
+    } else if c == '0' && i + 1 < chars.len() && "xXbBoO".contains(chars[i + 1]) {
+      let radix = match chars[i + 1].to_ascii_lowercase() {
+        'x' => 16,
+        'b' => 2,
+        _ => 8,
+      };
+      let start = i + 2;
+      i = start;
+      while i < chars.len() && chars[i].is_digit(radix) {
+        i += 1;
+      }
+
+      let digits = chars[start..i].iter().collect::<String>();
+      tokens.push(Token::Number(
+        i64::from_str_radix(&digits, radix).ok()? as f64
+      ));
+      radix_literal = true;
+    } else if c.is_ascii_digit() || c == '.' {
+      let start = i;
+      while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+        i += 1;
+      }
+
+      // exponent, only when followed by digits so `2e` is still 2 * e
+      if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+        let mut j = i + 1;
+        if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+          j += 1;
+        }
+        if j < chars.len() && chars[j].is_ascii_digit() {
+          i = j;
+          while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+          }
+        }
+      }
+
+      let number = chars[start..i].iter().collect::<String>();
+      tokens.push(Token::Number(number.parse().ok()?));
+    } else if c.is_alphabetic() {
+      let start = i;
+      while i < chars.len() && (chars[i].is_alphanumeric()) {
+        i += 1;
+      }
+      tokens.push(Token::Ident(
+        chars[start..i].iter().collect::<String>().to_lowercase(),
+      ));
+    } else {
+      let token = match c {
+        '(' => Token::LParen,
+        ')' => Token::RParen,
+        ',' => Token::Comma,
+        '*' if chars.get(i + 1) == Some(&'*') => {
+          i += 1;
+          Token::Op('^')
+        }
+        '×' => Token::Op('*'),
+        '÷' => Token::Op('/'),
+        '−' => Token::Op('-'),
+        '+' | '-' | '*' | '/' | '%' | '^' | '!' => Token::Op(c),
+        _ => return None,
+      };
+      tokens.push(token);
+      i += 1;
+    }
+  }
+
+  Some((tokens, radix_literal))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Value {
+  value: f64,
+  /// Set for `10%`, so `200 + 10%` adds 10% of 200 instead of 0.1
+  percent: bool,
+}
+
+impl Value {
+  fn new(value: f64) -> Self {
+    Self {
+      value,
+      percent: false,
+    }
+  }
+}
+
+struct Evaluated {
+  value: f64,
+  /// Whether the expression did anything beyond being a plain number.
+  nontrivial: bool,
+}
+
+/// How deep parentheses, function arguments and unary operators can be nested, so a query like
+/// `((((…` can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// A recursive descent parser, only numbers, operators and a fixed set of functions and
+/// constants are supported so nothing from the query is ever executed.
+struct Parser {
+  tokens: Vec<Token>,
+  pos: usize,
+  depth: usize,
+  nontrivial: bool,
+}
+
+impl Parser {
+  fn evaluate(input: &str) -> Option<Evaluated> {
+    let (tokens, radix_literal) = tokenize(input)?;
+    let mut parser = Parser {
+      tokens,
+      pos: 0,
+      depth: 0,
+      nontrivial: radix_literal,
+    };
+
+    if parser.tokens.is_empty() {
+      return None;
+    }
+
+    let value = parser.expr()?;
+    if parser.pos != parser.tokens.len() {
+      return None;
+    }
+
+    Some(Evaluated {
+      value: value.value,
+      nontrivial: parser.nontrivial,
+    })
+  }
+
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.pos)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.pos).cloned();
+    self.pos += 1;
+    token
+  }
+
+  /// Run a nested parse, failing once it's nested more than `MAX_DEPTH` levels deep.
+  fn nested<T>(&mut self, parse: fn(&mut Self) -> Option<T>) -> Option<T> {
+    if self.depth >= MAX_DEPTH {
+      return None;
+    }
+
+    self.depth += 1;
+    let value = parse(self);
+    self.depth -= 1;
+    value
+  }
+
+  fn expr(&mut self) -> Option<Value> {
+    let mut lhs = self.term()?;
+
+    while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+      self.pos += 1;
+      self.nontrivial = true;
+      let rhs = self.term()?;
+      let rhs = if rhs.percent {
+        lhs.value * rhs.value
+      } else {
+        rhs.value
+      };
+
+      lhs = Value::new(if op == '+' {
+        lhs.value + rhs
+      } else {
+        lhs.value - rhs
+      });
+    }
+
+    Some(lhs)
+  }
+
+  fn term(&mut self) -> Option<Value> {
+    let mut lhs = self.unary()?;
+
+    loop {
+      let op = match self.peek() {
+        Some(Token::Op(op @ ('*' | '/' | '%'))) => *op,
+        Some(Token::Ident(ident)) if ident == "mod" => '%',
+        // implicit multiplication: `2pi`, `3(4 + 1)`, but `2 3` isn't a valid expression
+        Some(Token::Ident(_)) | Some(Token::LParen) => {
+          self.nontrivial = true;
+          let rhs = self.unary()?;
+          lhs = Value::new(lhs.value * rhs.value);
+          continue;
+        }
+        _ => break,
+      };
+
+      self.pos += 1;
+      self.nontrivial = true;
+      let rhs = self.unary()?.value;
+
+      lhs = Value::new(match op {
+        '*' => lhs.value * rhs,
+        '/' => lhs.value / rhs,
+        _ => lhs.value % rhs,
+      });
+    }
+
+    Some(lhs)
+  }
+
+  fn unary(&mut self) -> Option<Value> {
+    match self.peek() {
+      Some(Token::Op('-')) => {
+        self.pos += 1;
+        let value = self.nested(Self::unary)?;
+        Some(Value {
+          value: -value.value,
+          percent: value.percent,
+        })
+      }
+      Some(Token::Op('+')) => {
+        self.pos += 1;
+        self.nested(Self::unary)
+      }
+      _ => self.power(),
+    }
+  }
+
+  fn power(&mut self) -> Option<Value> {
+    let base = self.postfix()?;
+
+    if let Some(Token::Op('^')) = self.peek() {
+      self.pos += 1;
+      self.nontrivial = true;
+      // right associative, and `2^-1` is allowed
+      let exponent = self.nested(Self::unary)?;
+      return Some(Value::new(base.value.powf(exponent.value)));
+    }
+
+    Some(base)
+  }
+
+  fn postfix(&mut self) -> Option<Value> {
+    let mut value = self.primary()?;
+
+    loop {
+      match self.peek() {
+        Some(Token::Op('!')) => {
+          self.pos += 1;
+          self.nontrivial = true;
+          value = Value::new(factorial(value.value)?);
+        }
+        Some(Token::Op('%')) => {
+          // `%` followed by an operand is modulo, otherwise it's a percentage
+          let operand_follows = matches!(
+            self.tokens.get(self.pos + 1),
+            Some(Token::Number(_)) | Some(Token::Ident(_)) | Some(Token::LParen)
+          );
+          if operand_follows {
+            break;
+          }
+
+          self.pos += 1;
+          self.nontrivial = true;
+          value = Value {
+            value: value.value / 100.0,
+            percent: true,
+          };
+        }
+        _ => break,
+      }
+    }
+
+    Some(value)
+  }
+
+  fn primary(&mut self) -> Option<Value> {
+    match self.next()? {
+      Token::Number(number) => Some(Value::new(number)),
+      Token::LParen => {
+        let value = self.nested(Self::expr)?;
+        match self.next()? {
+          Token::RParen => Some(value),
+          _ => None,
+        }
+      }
+      Token::Ident(ident) => {
+        self.nontrivial = true;
+
+        if let Some(constant) = constant(&ident) {
+          return Some(Value::new(constant));
+        }
+
+        let args = self.arguments()?;
+        Some(Value::new(function(&ident, &args)?))
+      }
+      _ => None,
+    }
+  }
+
+  /// Function arguments, `sqrt 16` is accepted as well as `sqrt(16)`.
+  fn arguments(&mut self) -> Option<Vec<f64>> {
+    if self.peek() != Some(&Token::LParen) {
+      return Some(vec![self.nested(Self::power)?.value]);
+    }
+
+    self.pos += 1;
+    let mut args = vec![self.nested(Self::expr)?.value];
+    loop {
+      match self.next()? {
+        Token::Comma => args.push(self.nested(Self::expr)?.value),
+        Token::RParen => return Some(args),
+        _ => return None,
+      }
+    }
+  }
+}
+
+fn constant(name: &str) -> Option<f64> {
+  match name {
+    "pi" | "π" => Some(consts::PI),
+    "e" => Some(consts::E),
+    "tau" | "τ" => Some(consts::TAU),
+    "phi" | "φ" => Some(1.618_033_988_749_895),
+    _ => None,
+  }
+}
+
+fn function(name: &str, args: &[f64]) -> Option<f64> {
+  let x = args[0];
+
+  let value = match (name, args.len()) {
+    ("sqrt", 1) => x.sqrt(),
+    ("cbrt", 1) => x.cbrt(),
+    ("abs", 1) => x.abs(),
+    ("sin", 1) => x.sin(),
+    ("cos", 1) => x.cos(),
+    ("tan", 1) => x.tan(),
+    ("asin", 1) => x.asin(),
+    ("acos", 1) => x.acos(),
+    ("atan", 1) => x.atan(),
+    ("sinh", 1) => x.sinh(),
+    ("cosh", 1) => x.cosh(),
+    ("tanh", 1) => x.tanh(),
+    ("ln", 1) => x.ln(),
+    ("log", 1) => x.log10(),
+    ("log", 2) => x.log(args[1]),
+    ("log2", 1) => x.log2(),
+    ("exp", 1) => x.exp(),
+    ("floor", 1) => x.floor(),
+    ("ceil", 1) => x.ceil(),
+    ("round", 1) => x.round(),
+    ("trunc", 1) => x.trunc(),
+    ("deg", 1) => x.to_degrees(),
+    ("rad", 1) => x.to_radians(),
+    ("pow", 2) => x.powf(args[1]),
+    ("min", _) => args.iter().cloned().fold(f64::INFINITY, f64::min),
+    ("max", _) => args.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+    _ => return None,
+  };
+
+  Some(value)
+}
+
+fn factorial(value: f64) -> Option<f64> {
+  if value < 0.0 || value.fract() != 0.0 || value > 170.0 {
+    return None;
+  }
+
+  Some((1..=value as u64).fold(1.0, |acc, n| acc * n as f64))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn value(query: &str) -> Option<String> {
+    Calculator::evaluate(query).map(|calculation| calculation.value)
+  }
+
+  #[test]
+  fn precedence() {
+    assert_eq!(value("2+2*3").as_deref(), Some("8"));
+    assert_eq!(value("(2+2)*3").as_deref(), Some("12"));
+    assert_eq!(value("2^3^2").as_deref(), Some("512"));
+    assert_eq!(value("-2^2").as_deref(), Some("-4"));
+    assert_eq!(value("10 - 4 - 3").as_deref(), Some("3"));
+    assert_eq!(value("200 + 10%").as_deref(), Some("220"));
+    assert_eq!(value("7 mod 4").as_deref(), Some("3"));
+  }
+
+  #[test]
+  fn implicit_multiplication() {
+    assert_eq!(value("3(4 + 1)").as_deref(), Some("15"));
+    assert_eq!(value("2pi").as_deref(), Some("6.2831853072"));
+    assert_eq!(value("2 3"), None);
+  }
+
+  #[test]
+  fn bases() {
+    assert_eq!(value("0xff + 1").as_deref(), Some("256"));
+    assert_eq!(value("255 in hex").as_deref(), Some("0xFF"));
+    assert_eq!(value("-5 in binary").as_deref(), Some("-0b101"));
+    assert_eq!(value("8 in oct").as_deref(), Some("0o10"));
+    assert_eq!(value("2^63 in hex").as_deref(), Some("0x8000000000000000"));
+    assert_eq!(value("2^64 in hex"), None);
+    assert_eq!(value("1.5 in hex"), None);
+  }
+
+  #[test]
+  fn errors() {
+    assert_eq!(value("42"), None);
+    assert_eq!(value("(1 + 2"), None);
+    assert_eq!(value("1 + 2)"), None);
+    assert_eq!(value("foo(1)"), None);
+    assert_eq!(value("1 / 0"), None);
+    assert_eq!(value("(-1)!"), None);
+  }
+
+  #[test]
+  fn depth_limit() {
+    let nested = |depth| format!("{}1+1{}", "(".repeat(depth), ")".repeat(depth));
+    assert_eq!(value(&nested(10)).as_deref(), Some("2"));
+    assert_eq!(value(&nested(100_000)), None);
+    assert_eq!(value(&format!("{}1", "-".repeat(100_000))), None);
+    assert_eq!(value(&format!("{}16", "sqrt ".repeat(100_000))), None);
+  }
+}
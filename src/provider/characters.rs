@@ -0,0 +1,225 @@
+use std::{
+  collections::HashMap,
+  fs::{read_to_string, write},
+  path::PathBuf,
+};
+
+use crate::{
+  fuzzy::get_score,
+  launcher::util::config::{CharacterPicker, Config},
+};
+
+/// Emoji skin tone names and their modifier characters.
+pub const SKIN_TONES: [(&str, char); 5] = [
+  ("light", '\u{1F3FB}'),
+  ("medium-light", '\u{1F3FC}'),
+  ("medium", '\u{1F3FD}'),
+  ("medium-dark", '\u{1F3FE}'),
+  ("dark", '\u{1F3FF}'),
+];
+
+/// Makes the preceding character render as an emoji instead of a text symbol.
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+/// How many recently used characters are remembered.
+const RECENT_SIZE: usize = 50;
+
+/// A character from the bundled table.
+#[derive(Debug, Clone)]
+pub struct CharacterInfo {
+  pub char: char,
+  /// Lowercase Unicode name, e.g. `thumbs up sign`.
+  pub name: &'static str,
+  pub emoji: bool,
+  /// Whether the emoji supports skin tone modifiers.
+  pub skin_tones: bool,
+  pub aliases: Vec<&'static str>,
+}
+
+/// A character ready to be copied, with its skin tone applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Character {
+  pub value: String,
+  pub name: String,
+  pub code: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Characters {
+  pub characters: Vec<CharacterInfo>,
+  /// Recently copied characters, most recent first.
+  pub recent: Vec<String>,
+  file: PathBuf,
+}
+
+impl Characters {
+  /// Parse the bundled character table and read recently used characters.
+  pub fn new(config: &Config) -> Self {
+    let mut aliases: HashMap<u32, Vec<&'static str>> = HashMap::new();
+    for (code, words) in rows(include_str!("../../data/unicode/emoji-aliases.txt")) {
+      aliases
+        .entry(code)
+        .or_default()
+        .extend(words.split_whitespace());
+    }
+
+    let characters = rows(include_str!("../../data/unicode/characters.txt"))
+      .filter_map(|(code, rest)| {
+        let (name, flags) = rest.split_once(';')?;
+
+        Some(CharacterInfo {
+          char: char::from_u32(code)?,
+          name,
+          emoji: flags.contains('e'),
+          skin_tones: flags.contains('m'),
+          aliases: aliases.remove(&code).unwrap_or_default(),
+        })
+      })
+      .collect();
+
+    let file = config.dir().join("recent_characters");
+    let recent = read_to_string(&file)
+      .map(|recent| recent.lines().map(|line| line.to_string()).collect())
+      .unwrap_or_default();
+
+    Self {
+      characters,
+      recent,
+      file,
+    }
+  }
+
+  /// Characters matching `query` by name or alias, recently used ones first. Skin tone variants of
+  /// emoji are only listed when the query names a skin tone, e.g. `thumbs up dark`.
+  pub fn search(
+    &self,
+    query: &str,
+    options: &CharacterPicker,
+    min_score: usize,
+    limit: usize,
+  ) -> Vec<Character> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+      return self
+        .recent
+        .iter()
+        .filter_map(|value| self.find(value))
+        .take(limit)
+        .collect();
+    }
+
+    let tone = SKIN_TONES
+      .iter()
+      .find(|(name, _)| query.split_whitespace().any(|word| word == *name));
+
+    let mut matches = vec![];
+    for info in &self.characters {
+      let mut candidates = vec![(variant(info, options.skin_tone), info.name.to_string())];
+      if let (Some((tone_name, modifier)), true) = (tone, info.skin_tones) {
+        let value = variant(info, Some(*modifier));
+        if value != candidates[0].0 {
+          candidates.push((value, format!("{}: {} skin tone", info.name, tone_name)));
+        }
+      }
+
+      for (value, name) in candidates {
+        let score = score(&query, &name, &info.aliases);
+        if score >= min_score {
+          matches.push((
+            Character {
+              value,
+              name,
+              code: info.char as u32,
+            },
+            score,
+          ));
+        }
+      }
+    }
+
+    // recently used characters first in the order they were used, then the best matches
+    matches.sort_by_key(|(character, score)| {
+      let recent = self
+        .recent
+        .iter()
+        .position(|value| *value == character.value);
+      (recent.unwrap_or(usize::MAX), usize::MAX - score)
+    });
+
+    matches
+      .into_iter()
+      .take(limit)
+      .map(|(character, _)| character)
+      .collect()
+  }
+
+  /// Look up a character, including skin tone variants, by its value.
+  fn find(&self, value: &str) -> Option<Character> {
+    let mut chars = value.chars();
+    let base = chars.next()?;
+    let modifier = chars.next().filter(|c| *c != EMOJI_PRESENTATION);
+    let info = self.characters.iter().find(|info| info.char == base)?;
+
+    let name = match SKIN_TONES.iter().find(|(_, m)| Some(*m) == modifier) {
+      Some((tone, _)) => format!("{}: {} skin tone", info.name, tone),
+      None => info.name.to_string(),
+    };
+
+    Some(Character {
+      value: value.to_string(),
+      name,
+      code: base as u32,
+    })
+  }
+
+  /// Remember a copied character, it's ranked first from now on.
+  pub fn save_recent(&mut self, value: &str) {
+    self.recent.retain(|recent| recent != value);
+    self.recent.insert(0, value.to_string());
+    self.recent.truncate(RECENT_SIZE);
+
+    write(&self.file, self.recent.join("\n")).unwrap();
+  }
+}
+
+/// The string that's copied for a character, emoji get a skin tone when they support it.
+fn variant(info: &CharacterInfo, skin_tone: Option<char>) -> String {
+  match skin_tone {
+    Some(modifier) if info.skin_tones => format!("{}{}", info.char, modifier),
+    _ if info.emoji && (info.char as u32) < 0x10000 => {
+      format!("{}{}", info.char, EMOJI_PRESENTATION)
+    }
+    _ => info.char.to_string(),
+  }
+}
+
+/// Score a character by its name and aliases, typing an alias exactly (e.g. `+1`) ranks it above
+/// any fuzzy name match.
+fn score(query: &str, name: &str, aliases: &[&str]) -> usize {
+  let alias_score = aliases
+    .iter()
+    .map(|alias| {
+      if *alias == query {
+        101
+      } else if alias.starts_with(query) {
+        95
+      } else {
+        get_score(query, &alias.replace('_', " ")) * 9 / 10
+      }
+    })
+    .max()
+    .unwrap_or(0);
+
+  alias_score.max(get_score(query, name))
+}
+
+/// `codepoint;rest` rows of a bundled table, skipping comments.
+fn rows(table: &'static str) -> impl Iterator<Item = (u32, &'static str)> {
+  table
+    .lines()
+    .filter(|line| !line.starts_with('#') && !line.is_empty())
+    .filter_map(|line| {
+      let (code, rest) = line.split_once(';')?;
+      Some((u32::from_str_radix(code, 16).ok()?, rest))
+    })
+}
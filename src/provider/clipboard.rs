@@ -0,0 +1,376 @@
+use std::{
+  fs::{read, remove_file, DirBuilder, OpenOptions, Permissions},
+  io::Write,
+  os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt},
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use chacha20poly1305::{
+  aead::{Aead, AeadCore, KeyInit, OsRng},
+  ChaCha20Poly1305, Key, Nonce,
+};
+use gtk::{
+  gdk::{Atom, SELECTION_CLIPBOARD},
+  gdk_pixbuf::{Pixbuf, PixbufLoader},
+  prelude::*,
+  Clipboard,
+};
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  fuzzy::get_score,
+  launcher::util::{
+    config::{ClipboardSettings, Config},
+    keyring::Keyring,
+  },
+};
+
+/// Length of the nonce that is prepended to every encrypted file.
+const NONCE_SIZE: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClipboardContent {
+  Text(String),
+  /// A PNG stored in the `clipboard` directory, `file` is relative to it.
+  Image {
+    file: String,
+    width: i32,
+    height: i32,
+  },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardItem {
+  pub content: ClipboardContent,
+  /// Unix timestamp of when the item was copied.
+  pub time: i64,
+}
+
+/// Text and images copied while dlauncher is running, most recent first.
+#[derive(Clone)]
+pub struct ClipboardHistory {
+  pub items: Vec<ClipboardItem>,
+  pub options: ClipboardSettings,
+  dir: PathBuf,
+  cipher: Option<ChaCha20Poly1305>,
+}
+
+impl std::fmt::Debug for ClipboardHistory {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ClipboardHistory")
+      .field("items", &self.items.len())
+      .field("options", &self.options)
+      .field("dir", &self.dir)
+      .field("encrypted", &self.cipher.is_some())
+      .finish()
+  }
+}
+
+impl ClipboardHistory {
+  /// Load the stored history in the background, `history` is filled in once it's loaded. With
+  /// `encrypt` that waits for the keyring to be unlocked, which can take as long as the user takes
+  /// to type their password.
+  pub fn load_in_background(config: &Config, history: Arc<Mutex<Option<Self>>>) {
+    if let Some(options) = config.clipboard_history() {
+      let dir = config.dir().join("clipboard");
+
+      std::thread::spawn(move || {
+        let loaded = Self::open(options, dir);
+        *history.lock().unwrap() = loaded;
+      });
+    }
+  }
+
+  /// Load the stored history, `None` when it can't be read or decrypted.
+  fn open(options: ClipboardSettings, dir: PathBuf) -> Option<Self> {
+    DirBuilder::new().recursive(true).mode(0o700).create(&dir).unwrap();
+
+    let cipher = if options.encrypt {
+      match read_key(&dir.join("history")) {
+        Ok(key) => Some(ChaCha20Poly1305::new(&key)),
+        Err(err) => {
+          error!(
+            "Failed to get the clipboard history key, history is disabled: {}",
+            err
+          );
+          return None;
+        }
+      }
+    } else {
+      None
+    };
+
+    let mut history = Self {
+      items: vec![],
+      options,
+      dir,
+      cipher,
+    };
+    // the stored history is left alone instead of being overwritten when it can't be read
+    history.items = history.load()?;
+
+    Some(history)
+  }
+
+  fn history_file(&self) -> PathBuf {
+    self.dir.join("history")
+  }
+
+  fn load(&self) -> Option<Vec<ClipboardItem>> {
+    let contents = match read(self.history_file()) {
+      Ok(contents) => contents,
+      Err(_) => return Some(vec![]),
+    };
+
+    let items = self
+      .decrypt(&contents)
+      .and_then(|contents| serde_json::from_slice(&contents).ok());
+    if items.is_none() {
+      error!(
+        "Failed to read the clipboard history, it was stored with different `encrypt` settings or is corrupt. History is disabled, remove {} to start over",
+        self.dir.display()
+      );
+    }
+
+    items
+  }
+
+  fn save(&self) {
+    let contents = serde_json::to_vec(&self.items).unwrap();
+    if let Err(err) = write_private(&self.history_file(), &self.encrypt(&contents)) {
+      error!("Failed to save the clipboard history: {}", err);
+    }
+  }
+
+  fn encrypt(&self, contents: &[u8]) -> Vec<u8> {
+    match &self.cipher {
+      Some(cipher) => {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend(cipher.encrypt(&nonce, contents).unwrap());
+        encrypted
+      }
+      None => contents.to_vec(),
+    }
+  }
+
+  fn decrypt(&self, contents: &[u8]) -> Option<Vec<u8>> {
+    match &self.cipher {
+      Some(cipher) => {
+        if contents.len() < NONCE_SIZE {
+          return None;
+        }
+        let (nonce, ciphertext) = contents.split_at(NONCE_SIZE);
+        cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok()
+      }
+      None => Some(contents.to_vec()),
+    }
+  }
+
+  /// Record copied text, copying something that is already in the history moves it to the top.
+  pub fn add_text(&mut self, text: String) {
+    if text.trim().is_empty() {
+      return;
+    }
+
+    self.push(ClipboardContent::Text(text));
+  }
+
+  /// Record a copied image, it's stored as a PNG next to the history.
+  pub fn add_image(&mut self, image: &Pixbuf) {
+    if !self.options.images {
+      return;
+    }
+
+    let png = match image.save_to_bufferv("png", &[]) {
+      Ok(png) => png,
+      Err(err) => {
+        error!("Failed to encode copied image: {}", err);
+        return;
+      }
+    };
+
+    // identical images are stored once
+    let file = format!("{:016x}.png", fnv1a(&png));
+    if let Err(err) = write_private(&self.dir.join(&file), &self.encrypt(&png)) {
+      error!("Failed to save copied image: {}", err);
+      return;
+    }
+
+    self.push(ClipboardContent::Image {
+      file,
+      width: image.width(),
+      height: image.height(),
+    });
+  }
+
+  fn push(&mut self, content: ClipboardContent) {
+    if self.items.first().map(|item| &item.content) == Some(&content) {
+      return;
+    }
+
+    self.items.retain(|item| item.content != content);
+    self.items.insert(
+      0,
+      ClipboardItem {
+        content,
+        time: SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .map(|time| time.as_secs() as i64)
+          .unwrap_or_default(),
+      },
+    );
+
+    if self.items.len() > self.options.max_entries {
+      for item in self.items.split_off(self.options.max_entries) {
+        if let ClipboardContent::Image { file, .. } = item.content {
+          let _ = remove_file(self.dir.join(file));
+        }
+      }
+    }
+
+    self.save();
+  }
+
+  /// Forget an item, its image is deleted along with it.
+  pub fn remove(&mut self, content: &ClipboardContent) {
+    self.items.retain(|item| item.content != *content);
+    if let ClipboardContent::Image { file, .. } = content {
+      let _ = remove_file(self.dir.join(file));
+    }
+
+    self.save();
+  }
+
+  /// Decode a stored image.
+  pub fn load_image(&self, file: &str) -> Option<Pixbuf> {
+    let contents = self.decrypt(&read(self.dir.join(file)).ok()?)?;
+    let loader = PixbufLoader::new();
+    loader.write(&contents).ok()?;
+    loader.close().ok()?;
+    loader.pixbuf()
+  }
+
+  /// Whether the clipboard should be ignored because one of its targets is excluded, e.g. a
+  /// password copied from a password manager.
+  pub fn is_excluded(&self, targets: &[Atom]) -> bool {
+    targets.iter().any(|target| {
+      self
+        .options
+        .exclude_targets
+        .contains(&target.name().to_string())
+    })
+  }
+
+  /// History items matching `query`, all of them when it's empty. Images only match an empty query.
+  pub fn search(&self, query: &str, min_score: usize) -> Vec<ClipboardItem> {
+    if query.is_empty() {
+      return self.items.clone();
+    }
+
+    let query = query.to_lowercase();
+    self
+      .items
+      .iter()
+      .filter(|item| match &item.content {
+        ClipboardContent::Text(text) => {
+          text.to_lowercase().contains(&query) || get_score(&query, text) >= min_score
+        }
+        ClipboardContent::Image { .. } => false,
+      })
+      .cloned()
+      .collect()
+  }
+
+  /// Start recording everything that is copied to the clipboard, nothing is recorded until the
+  /// history is loaded.
+  pub fn watch(history: Arc<Mutex<Option<Self>>>) {
+    let clipboard = Clipboard::get(&SELECTION_CLIPBOARD);
+
+    clipboard.connect_local("owner-change", false, move |_| {
+      let history = history.clone();
+      let clipboard = Clipboard::get(&SELECTION_CLIPBOARD);
+
+      clipboard.request_contents(&Atom::intern("TARGETS"), move |clipboard, data| {
+        let targets = data.targets().unwrap_or_default();
+        match &*history.lock().unwrap() {
+          Some(history) if history.is_excluded(&targets) => {
+            debug!("Ignoring clipboard contents with an excluded target");
+            return;
+          }
+          Some(_) => {}
+          None => {
+            debug!("Ignoring clipboard contents, the history isn't loaded");
+            return;
+          }
+        }
+
+        if data.targets_include_image(false) {
+          let history = history.clone();
+          clipboard.request_image(move |_, image| {
+            if let (Some(image), Some(history)) = (image, &mut *history.lock().unwrap()) {
+              history.add_image(image);
+            }
+          });
+        } else if data.targets_include_text() {
+          let history = history.clone();
+          clipboard.request_text(move |_, text| {
+            if let (Some(text), Some(history)) = (text, &mut *history.lock().unwrap()) {
+              history.add_text(text.to_string());
+            }
+          });
+        }
+      });
+
+      None
+    });
+  }
+}
+
+/// Get the history encryption key from the keyring, generating it the first time.
+fn read_key(history_file: &Path) -> Result<Key, String> {
+  let keyring = Keyring::new("clipboard-history").map_err(|err| err.to_string())?;
+
+  match keyring.get().map_err(|err| err.to_string())? {
+    Some(key) if key.len() == 32 => Ok(*Key::from_slice(&key)),
+    Some(key) => Err(format!(
+      "the key in the keyring is {} bytes long instead of 32",
+      key.len()
+    )),
+    // a new key can't decrypt the existing history
+    None if history_file.exists() => Err(format!(
+      "the key isn't in the keyring anymore, remove {} to start over",
+      history_file.display()
+    )),
+    None => {
+      let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+      keyring
+        .set("dlauncher clipboard history", &key)
+        .map_err(|err| err.to_string())?;
+      Ok(key)
+    }
+  }
+}
+
+/// Write a file that only the user can read, the history and images can contain passwords.
+fn write_private(file: &Path, contents: &[u8]) -> std::io::Result<()> {
+  let mut file = OpenOptions::new()
+    .write(true)
+    .create(true)
+    .truncate(true)
+    .mode(0o600)
+    .open(file)?;
+  // `mode` only applies to new files
+  file.set_permissions(Permissions::from_mode(0o600))?;
+  file.write_all(contents)
+}
+
+/// FNV-1a, used to name stored images after their contents.
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+  })
+}
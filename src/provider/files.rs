@@ -0,0 +1,72 @@
+use std::{collections::VecDeque, fs::read_dir, path::PathBuf};
+
+use glob::Pattern;
+use log::{debug, error};
+
+use crate::launcher::util::config::FileSearch;
+
+pub struct Files;
+
+impl Files {
+  /// Walk the configured directories and collect every file and folder that isn't ignored. This
+  /// can take a while on large directories so it should be called outside of the GTK thread.
+  pub fn index(options: &FileSearch) -> Vec<PathBuf> {
+    debug!("Indexing files");
+    let ignore = options
+      .ignore
+      .iter()
+      .filter_map(|pattern| match Pattern::new(pattern) {
+        Ok(pattern) => Some(pattern),
+        Err(err) => {
+          error!("Invalid file_search ignore pattern {:?}: {}", pattern, err);
+          None
+        }
+      })
+      .collect::<Vec<Pattern>>();
+
+    let mut files = Vec::new();
+    let mut queue = options
+      .directories
+      .iter()
+      .map(|dir| (dir.clone(), 0))
+      .collect::<VecDeque<(PathBuf, usize)>>();
+
+    // breadth first, so the max_files limit cuts off the deepest files instead of whole folders.
+    while let Some((dir, depth)) = queue.pop_front() {
+      let entries = match read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+          debug!("Couldn't read {}: {}", dir.display(), err);
+          continue;
+        }
+      };
+
+      for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !options.show_hidden && name.starts_with('.') {
+          continue;
+        }
+
+        if ignore.iter().any(|pattern| pattern.matches(&name)) {
+          continue;
+        }
+
+        let path = entry.path();
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if is_dir && depth + 1 < options.max_depth {
+          queue.push_back((path.clone(), depth + 1));
+        }
+
+        files.push(path);
+        if files.len() >= options.max_files {
+          debug!("Reached max_files, indexed {} files", files.len());
+          return files;
+        }
+      }
+    }
+
+    debug!("Indexed {} files", files.len());
+
+    files
+  }
+}
@@ -0,0 +1,16 @@
+/// Calculator and unit converter
+pub mod calculator;
+/// Emoji and Unicode characters
+pub mod characters;
+/// Clipboard history
+pub mod clipboard;
+/// File and folder search
+pub mod files;
+/// Lock, log out, suspend, reboot and shut down
+pub mod session;
+/// Shell command mode
+pub mod shell;
+/// Quicklinks and web search
+pub mod web;
+/// Open windows on X11
+pub mod windows;
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use dbus::blocking::Connection;
+use log::{debug, error};
+
+use crate::{launcher::util::config::ConfigSession, util::launch_detached};
+
+const LOGIND: &str = "org.freedesktop.login1";
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+/// The session dlauncher is running in.
+const LOGIND_SESSION_PATH: &str = "/org/freedesktop/login1/session/auto";
+const LOGIND_SESSION: &str = "org.freedesktop.login1.Session";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionAction {
+  Lock,
+  LogOut,
+  Suspend,
+  Hibernate,
+  Reboot,
+  ShutDown,
+}
+
+impl SessionAction {
+  pub const ALL: [SessionAction; 6] = [
+    SessionAction::Lock,
+    SessionAction::LogOut,
+    SessionAction::Suspend,
+    SessionAction::Hibernate,
+    SessionAction::Reboot,
+    SessionAction::ShutDown,
+  ];
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      SessionAction::Lock => "Lock",
+      SessionAction::LogOut => "Log out",
+      SessionAction::Suspend => "Suspend",
+      SessionAction::Hibernate => "Hibernate",
+      SessionAction::Reboot => "Reboot",
+      SessionAction::ShutDown => "Shut down",
+    }
+  }
+
+  pub fn description(&self) -> &'static str {
+    match self {
+      SessionAction::Lock => "Lock the screen",
+      SessionAction::LogOut => "End the current session",
+      SessionAction::Suspend => "Suspend to RAM",
+      SessionAction::Hibernate => "Suspend to disk",
+      SessionAction::Reboot => "Restart the computer",
+      SessionAction::ShutDown => "Power off the computer",
+    }
+  }
+
+  /// Other names the action is matched by.
+  pub fn keywords(&self) -> &'static [&'static str] {
+    match self {
+      SessionAction::Lock => &["lock screen"],
+      SessionAction::LogOut => &["logout", "sign out", "exit"],
+      SessionAction::Suspend => &["sleep"],
+      SessionAction::Hibernate => &[],
+      SessionAction::Reboot => &["restart"],
+      SessionAction::ShutDown => &["shutdown", "power off", "poweroff", "halt"],
+    }
+  }
+
+  pub fn icon(&self) -> &'static str {
+    match self {
+      SessionAction::Lock => "system-lock-screen",
+      SessionAction::LogOut => "system-log-out",
+      SessionAction::Suspend => "system-suspend",
+      SessionAction::Hibernate => "system-suspend-hibernate",
+      SessionAction::Reboot => "system-reboot",
+      SessionAction::ShutDown => "system-shutdown",
+    }
+  }
+
+  /// Whether the action ends the session or turns the computer off, these ask for confirmation.
+  pub fn is_destructive(&self) -> bool {
+    matches!(
+      self,
+      SessionAction::LogOut | SessionAction::Reboot | SessionAction::ShutDown
+    )
+  }
+
+  /// The configured command that replaces the logind call.
+  pub fn command<'a>(&self, options: &'a ConfigSession) -> Option<&'a String> {
+    match self {
+      SessionAction::Lock => options.lock.as_ref(),
+      SessionAction::LogOut => options.log_out.as_ref(),
+      SessionAction::Suspend => options.suspend.as_ref(),
+      SessionAction::Hibernate => options.hibernate.as_ref(),
+      SessionAction::Reboot => options.reboot.as_ref(),
+      SessionAction::ShutDown => options.shut_down.as_ref(),
+    }
+  }
+
+  /// logind's `Can*` method for the action, lock and log out are always possible.
+  fn logind_check(&self) -> Option<&'static str> {
+    match self {
+      SessionAction::Suspend => Some("CanSuspend"),
+      SessionAction::Hibernate => Some("CanHibernate"),
+      SessionAction::Reboot => Some("CanReboot"),
+      SessionAction::ShutDown => Some("CanPowerOff"),
+      SessionAction::Lock | SessionAction::LogOut => None,
+    }
+  }
+
+  /// Actions that have a command or that logind allows, `Hibernate` is hidden on most systems
+  /// without a swap partition.
+  pub fn available(options: &ConfigSession) -> Vec<SessionAction> {
+    let conn = Connection::new_system().ok();
+
+    SessionAction::ALL
+      .into_iter()
+      .filter(|action| {
+        if action.command(options).is_some() {
+          return true;
+        }
+
+        match (action.logind_check(), &conn) {
+          (None, _) => true,
+          (Some(method), Some(conn)) => {
+            let proxy = conn.with_proxy(LOGIND, LOGIND_PATH, Duration::from_millis(1000));
+            let result: Result<(String,), _> = proxy.method_call(LOGIND_MANAGER, method, ());
+            // `challenge` means polkit will ask for a password
+            matches!(
+              result.as_ref().map(|(r,)| r.as_str()),
+              Ok("yes" | "challenge")
+            )
+          }
+          (Some(_), None) => false,
+        }
+      })
+      .collect()
+  }
+
+  pub fn run(&self, options: &ConfigSession) {
+    if let Some(command) = self.command(options) {
+      debug!("Attempting to run {:?}", command);
+      launch_detached(vec!["sh", "-c", command], vec![]);
+      return;
+    }
+
+    debug!("Requesting {} from logind", self.name());
+    if let Err(err) = self.call_logind() {
+      error!("Failed to {}: {}", self.name().to_lowercase(), err);
+    }
+  }
+
+  fn call_logind(&self) -> Result<(), dbus::Error> {
+    let conn = Connection::new_system()?;
+    let timeout = Duration::from_millis(5000);
+
+    let method = match self {
+      SessionAction::Lock => "Lock",
+      SessionAction::LogOut => "Terminate",
+      SessionAction::Suspend => "Suspend",
+      SessionAction::Hibernate => "Hibernate",
+      SessionAction::Reboot => "Reboot",
+      SessionAction::ShutDown => "PowerOff",
+    };
+
+    match self {
+      SessionAction::Lock | SessionAction::LogOut => conn
+        .with_proxy(LOGIND, LOGIND_SESSION_PATH, timeout)
+        .method_call(LOGIND_SESSION, method, ()),
+      // `true` lets polkit ask for authentication when it's needed
+      _ => {
+        conn
+          .with_proxy(LOGIND, LOGIND_PATH, timeout)
+          .method_call(LOGIND_MANAGER, method, (true,))
+      }
+    }
+  }
+}
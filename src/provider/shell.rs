@@ -0,0 +1,183 @@
+use std::{
+  collections::HashSet,
+  fs::{read, read_dir, write},
+  os::unix::fs::PermissionsExt,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use log::debug;
+
+use crate::launcher::util::config::Config;
+
+/// How many commands are kept in dlauncher's own command history.
+const HISTORY_SIZE: usize = 500;
+
+/// Completions for shell command mode, executables in `$PATH` and previously run commands.
+#[derive(Debug, Clone)]
+pub struct Shell {
+  pub executables: Vec<String>,
+  /// Most recent first, without duplicates.
+  pub history: Vec<String>,
+  file: PathBuf,
+  /// Modification times of the `$PATH` directories and history files that were read, so they are
+  /// only read again when one of them changes.
+  path_dirs: Vec<(PathBuf, Option<SystemTime>)>,
+  history_files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Shell {
+  /// Read executables and history, nothing is read when shell command mode is disabled.
+  pub fn new(config: &Config) -> Self {
+    let mut shell = Self {
+      executables: vec![],
+      history: vec![],
+      file: config.dir().join("shell_history"),
+      path_dirs: vec![],
+      history_files: vec![],
+    };
+    shell.refresh(config);
+    shell
+  }
+
+  /// Read executables and history again, but only if `$PATH`, one of its directories or one of
+  /// the history files changed since they were last read.
+  pub fn refresh(&mut self, config: &Config) {
+    let options = match config.shell_mode() {
+      Some(options) => options,
+      None => return,
+    };
+
+    let path = std::env::var("PATH").unwrap_or_default();
+    let path_dirs = modified(path.split(':').map(PathBuf::from));
+    if path_dirs != self.path_dirs {
+      debug!("Reading executables");
+      self.executables = executables(&path_dirs);
+      self.path_dirs = path_dirs;
+    }
+
+    // dlauncher's history first, so commands run from the launcher are completed first.
+    let history_files = modified(std::iter::once(self.file.clone()).chain(options.history_file));
+    if history_files != self.history_files {
+      debug!("Reading shell history");
+      let mut history = history_files
+        .iter()
+        .flat_map(|(file, _)| read_history(file))
+        .collect::<Vec<String>>();
+
+      let mut seen = HashSet::new();
+      history.retain(|command| seen.insert(command.clone()));
+
+      self.history = history;
+      self.history_files = history_files;
+    }
+  }
+
+  /// Completions for a partially typed command, at most `limit` are returned.
+  pub fn complete(&self, command: &str, limit: usize) -> Vec<String> {
+    let mut completions = self
+      .history
+      .iter()
+      .filter(|entry| entry.starts_with(command) && entry.as_str() != command)
+      .take(limit)
+      .cloned()
+      .collect::<Vec<String>>();
+
+    // executables are only completed while the first word is being typed
+    if !command.is_empty() && !command.contains(char::is_whitespace) {
+      let mut executables = self
+        .executables
+        .iter()
+        .filter(|exe| exe.starts_with(command) && exe.as_str() != command)
+        .filter(|exe| !completions.contains(exe))
+        .collect::<Vec<&String>>();
+      executables.sort_by_key(|exe| exe.len());
+
+      completions.extend(executables.into_iter().cloned());
+    }
+
+    completions.truncate(limit);
+    completions
+  }
+
+  /// Record a command that was run, it will be completed first from now on.
+  pub fn save_command(&mut self, command: &str) {
+    self.history.retain(|entry| entry != command);
+    self.history.insert(0, command.to_string());
+
+    let own = read_history(&self.file)
+      .into_iter()
+      .filter(|entry| entry != command)
+      .take(HISTORY_SIZE - 1)
+      .collect::<Vec<String>>();
+
+    // stored oldest first, like a shell history
+    let mut lines = own.into_iter().rev().collect::<Vec<String>>();
+    lines.push(command.to_string());
+    write(&self.file, lines.join("\n")).unwrap();
+  }
+}
+
+/// Pair every path with its modification time, `None` when it doesn't exist.
+fn modified(paths: impl Iterator<Item = PathBuf>) -> Vec<(PathBuf, Option<SystemTime>)> {
+  paths
+    .map(|path| {
+      let modified = path.metadata().and_then(|meta| meta.modified()).ok();
+      (path, modified)
+    })
+    .collect()
+}
+
+/// Every executable file in the `$PATH` directories, sorted and without duplicates.
+fn executables(dirs: &[(PathBuf, Option<SystemTime>)]) -> Vec<String> {
+  let mut executables = dirs
+    .iter()
+    .filter_map(|(dir, _)| read_dir(dir).ok())
+    .flat_map(|entries| entries.flatten())
+    .filter(|entry| {
+      entry
+        .metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+    })
+    .map(|entry| entry.file_name().to_string_lossy().to_string())
+    .collect::<Vec<String>>();
+
+  executables.sort();
+  executables.dedup();
+  executables
+}
+
+/// Read a bash, zsh (plain and extended) or fish history file, most recent command first.
+fn read_history(file: &Path) -> Vec<String> {
+  let contents = match read(file) {
+    Ok(contents) => String::from_utf8_lossy(&contents).to_string(),
+    Err(_) => return vec![],
+  };
+
+  contents
+    .lines()
+    .filter_map(|line| {
+      let command = if let Some(cmd) = line.strip_prefix("- cmd: ") {
+        // fish
+        cmd
+      } else if line.starts_with(": ") && line.contains(';') {
+        // zsh extended history, `: 1650000000:0;command`
+        line.split_once(';').map(|(_, cmd)| cmd).unwrap_or(line)
+      } else if line.starts_with('#') || line.starts_with("  ") {
+        // bash timestamps and fish metadata
+        return None;
+      } else {
+        line
+      };
+
+      let command = command.trim();
+      if command.is_empty() {
+        None
+      } else {
+        Some(command.to_string())
+      }
+    })
+    .rev()
+    .collect()
+}
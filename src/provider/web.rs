@@ -0,0 +1,44 @@
+use gtk::glib::uri_escape_string;
+
+use crate::{
+  entry::web_entry::WebEntry,
+  launcher::util::config::{Quicklink, WebSearch},
+  util::matches,
+};
+
+/// Substitute `%s` in a url template with the url encoded query. Slashes are kept so quicklinks
+/// like `https://github.com/%s` can take paths.
+pub fn url_for(template: &str, query: &str) -> String {
+  template.replace("%s", &uri_escape_string(query, Some("/"), true))
+}
+
+/// Quicklinks matching the query. A quicklink matches when the query starts with its keyword
+/// (`gh rust-lang/rust`), or when the query fuzzy matches its name or keyword.
+pub fn quicklinks(quicklinks: &[Quicklink], query: &str, min_score: usize) -> Vec<WebEntry> {
+  let (keyword, argument) = query.split_once(' ').unwrap_or((query, ""));
+  let argument = argument.trim();
+
+  quicklinks
+    .iter()
+    .filter_map(|link| {
+      if link.keyword.eq_ignore_ascii_case(keyword) {
+        Some(WebEntry::quicklink(link, argument))
+      } else if matches(query, &link.name, min_score).is_some()
+        || matches(query, &link.keyword, min_score).is_some()
+      {
+        Some(WebEntry::quicklink(link, ""))
+      } else {
+        None
+      }
+    })
+    .collect()
+}
+
+/// The "Search the web" fallback for a query.
+pub fn web_search(search: &WebSearch, query: &str) -> WebEntry {
+  WebEntry::new(
+    format!("Search {} for “{}”", search.name, query),
+    url_for(&search.url, query),
+    None,
+  )
+}
@@ -0,0 +1,443 @@
+use std::{
+  ffi::CString,
+  os::raw::{c_char, c_int, c_long, c_uchar, c_uint, c_ulong},
+  ptr, slice,
+  time::{Duration, Instant},
+};
+
+use log::{debug, error};
+
+type XDisplay = std::ffi::c_void;
+type XWindow = c_ulong;
+type Atom = c_ulong;
+
+const SUCCESS: c_int = 0;
+const ANY_PROPERTY_TYPE: Atom = 0;
+const CLIENT_MESSAGE: c_int = 33;
+const PROP_MODE_REPLACE: c_int = 0;
+const SUBSTRUCTURE_NOTIFY_MASK: c_long = 1 << 19;
+const SUBSTRUCTURE_REDIRECT_MASK: c_long = 1 << 20;
+/// Source indication of `_NET_ACTIVE_WINDOW` requests, 2 means a pager, which window managers
+/// don't apply focus stealing prevention to.
+const SOURCE_PAGER: c_long = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct XClientMessageEvent {
+  type_: c_int,
+  serial: c_ulong,
+  send_event: c_int,
+  display: *mut XDisplay,
+  window: XWindow,
+  message_type: Atom,
+  format: c_int,
+  data: [c_long; 5],
+}
+
+#[repr(C)]
+union XEvent {
+  type_: c_int,
+  client_message: XClientMessageEvent,
+  pad: [c_long; 24],
+}
+
+#[link(name = "X11")]
+extern "C" {
+  fn XOpenDisplay(name: *const c_char) -> *mut XDisplay;
+  fn XCloseDisplay(display: *mut XDisplay) -> c_int;
+  fn XDefaultRootWindow(display: *mut XDisplay) -> XWindow;
+  fn XInternAtom(display: *mut XDisplay, name: *const c_char, only_if_exists: c_int) -> Atom;
+  fn XGetWindowProperty(
+    display: *mut XDisplay,
+    window: XWindow,
+    property: Atom,
+    long_offset: c_long,
+    long_length: c_long,
+    delete: c_int,
+    req_type: Atom,
+    actual_type: *mut Atom,
+    actual_format: *mut c_int,
+    nitems: *mut c_ulong,
+    bytes_after: *mut c_ulong,
+    prop: *mut *mut c_uchar,
+  ) -> c_int;
+  fn XSendEvent(
+    display: *mut XDisplay,
+    window: XWindow,
+    propagate: c_int,
+    event_mask: c_long,
+    event: *mut XEvent,
+  ) -> c_int;
+  fn XFlush(display: *mut XDisplay) -> c_int;
+  fn XFree(data: *mut std::ffi::c_void) -> c_int;
+  fn XCreateSimpleWindow(
+    display: *mut XDisplay,
+    parent: XWindow,
+    x: c_int,
+    y: c_int,
+    width: c_uint,
+    height: c_uint,
+    border_width: c_uint,
+    border: c_ulong,
+    background: c_ulong,
+  ) -> XWindow;
+  fn XDestroyWindow(display: *mut XDisplay, window: XWindow) -> c_int;
+  fn XChangeProperty(
+    display: *mut XDisplay,
+    window: XWindow,
+    property: Atom,
+    kind: Atom,
+    format: c_int,
+    mode: c_int,
+    data: *const c_uchar,
+    nelements: c_int,
+  ) -> c_int;
+  fn XDeleteProperty(display: *mut XDisplay, window: XWindow, property: Atom) -> c_int;
+  fn XSelectInput(display: *mut XDisplay, window: XWindow, event_mask: c_long) -> c_int;
+  fn XSync(display: *mut XDisplay, discard: c_int) -> c_int;
+  fn XPending(display: *mut XDisplay) -> c_int;
+  fn XNextEvent(display: *mut XDisplay, event: *mut XEvent) -> c_int;
+}
+
+/// A top level window managed by the window manager.
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+  /// X window id.
+  pub id: c_ulong,
+  pub title: String,
+  /// Instance part of WM_CLASS, e.g. `navigator`.
+  pub instance: String,
+  /// Class part of WM_CLASS, e.g. `firefox`, compared with `StartupWMClass` of desktop entries.
+  pub class: String,
+}
+
+impl WindowInfo {
+  /// Whether the window belongs to an app with the given `StartupWMClass`, compared case
+  /// insensitively to both parts of WM_CLASS like most docks do.
+  pub fn has_class(&self, wm_class: &str) -> bool {
+    self.class.eq_ignore_ascii_case(wm_class) || self.instance.eq_ignore_ascii_case(wm_class)
+  }
+}
+
+/// A connection to the X server, closed when dropped.
+struct Connection {
+  display: *mut XDisplay,
+  root: XWindow,
+}
+
+impl Connection {
+  fn open() -> Option<Self> {
+    std::env::var_os("DISPLAY")?;
+
+    let display = unsafe { XOpenDisplay(ptr::null()) };
+    if display.is_null() {
+      debug!("Couldn't connect to the X server, window switching is unavailable");
+      return None;
+    }
+
+    let root = unsafe { XDefaultRootWindow(display) };
+    Some(Self { display, root })
+  }
+
+  fn atom(&self, name: &str) -> Atom {
+    let name = CString::new(name).unwrap();
+    unsafe { XInternAtom(self.display, name.as_ptr(), 0) }
+  }
+
+  /// Raw contents of a property along with its format (8, 16 or 32).
+  fn property(&self, window: XWindow, property: &str, kind: Atom) -> Option<(c_int, Vec<u8>)> {
+    let mut actual_type = 0;
+    let mut format = 0;
+    let mut nitems = 0;
+    let mut bytes_after = 0;
+    let mut data = ptr::null_mut();
+
+    let status = unsafe {
+      XGetWindowProperty(
+        self.display,
+        window,
+        self.atom(property),
+        0,
+        // in 32 bit units, enough for any title or client list
+        4096,
+        0,
+        kind,
+        &mut actual_type,
+        &mut format,
+        &mut nitems,
+        &mut bytes_after,
+        &mut data,
+      )
+    };
+
+    if status != SUCCESS || data.is_null() {
+      return None;
+    }
+
+    // 32 bit items are returned as longs
+    let item_size = match format {
+      8 => 1,
+      16 => std::mem::size_of::<i16>(),
+      32 => std::mem::size_of::<c_long>(),
+      _ => 0,
+    };
+    let bytes = unsafe { slice::from_raw_parts(data, nitems as usize * item_size) }.to_vec();
+    unsafe { XFree(data as *mut _) };
+
+    if actual_type == 0 {
+      None
+    } else {
+      Some((format, bytes))
+    }
+  }
+
+  fn longs(&self, window: XWindow, property: &str, kind: Atom) -> Vec<c_ulong> {
+    match self.property(window, property, kind) {
+      Some((32, bytes)) => bytes
+        .chunks_exact(std::mem::size_of::<c_ulong>())
+        .map(|chunk| c_ulong::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect(),
+      _ => vec![],
+    }
+  }
+
+  fn string(&self, window: XWindow, property: &str, kind: Atom) -> Option<String> {
+    match self.property(window, property, kind) {
+      Some((8, bytes)) => Some(String::from_utf8_lossy(&bytes).to_string()),
+      _ => None,
+    }
+  }
+
+  fn window(&self, id: XWindow) -> WindowInfo {
+    let title = self
+      .string(id, "_NET_WM_NAME", self.atom("UTF8_STRING"))
+      .or_else(|| self.string(id, "WM_NAME", ANY_PROPERTY_TYPE))
+      .unwrap_or_default();
+
+    // WM_CLASS is two null terminated strings, the instance and the class
+    let wm_class = self
+      .property(id, "WM_CLASS", ANY_PROPERTY_TYPE)
+      .map(|(_, bytes)| bytes)
+      .unwrap_or_default();
+    let mut parts = wm_class
+      .split(|byte| *byte == 0)
+      .map(|part| String::from_utf8_lossy(part).to_string());
+    let instance = parts.next().unwrap_or_default();
+    let class = parts.next().unwrap_or_default();
+
+    WindowInfo {
+      id,
+      title,
+      instance,
+      class,
+    }
+  }
+
+  /// Whether a window asked not to be shown in task switchers, or is dlauncher itself.
+  fn is_skipped(&self, id: XWindow) -> bool {
+    let skip_taskbar = self.atom("_NET_WM_STATE_SKIP_TASKBAR");
+    let state = self.longs(id, "_NET_WM_STATE", ANY_PROPERTY_TYPE);
+    let pid = self.longs(id, "_NET_WM_PID", ANY_PROPERTY_TYPE);
+
+    state.contains(&skip_taskbar) || pid.first() == Some(&(std::process::id() as c_ulong))
+  }
+}
+
+impl Drop for Connection {
+  fn drop(&mut self) {
+    unsafe { XCloseDisplay(self.display) };
+  }
+}
+
+/// Open windows on X11, read from and activated through the EWMH properties of the root window.
+/// Nothing is listed without an X server or a EWMH compliant window manager.
+pub struct Windows;
+
+impl Windows {
+  /// Every window in `_NET_CLIENT_LIST`, in stacking order when the window manager supports
+  /// `_NET_CLIENT_LIST_STACKING` so the most recently used windows come first.
+  pub fn list() -> Vec<WindowInfo> {
+    let conn = match Connection::open() {
+      Some(conn) => conn,
+      None => return vec![],
+    };
+
+    let mut clients = conn.longs(conn.root, "_NET_CLIENT_LIST_STACKING", ANY_PROPERTY_TYPE);
+    if clients.is_empty() {
+      clients = conn.longs(conn.root, "_NET_CLIENT_LIST", ANY_PROPERTY_TYPE);
+    }
+    clients.reverse();
+
+    clients
+      .into_iter()
+      .filter(|id| !conn.is_skipped(*id))
+      .map(|id| conn.window(id))
+      .filter(|window| !window.title.is_empty())
+      .collect()
+  }
+
+  /// Ask the window manager to switch to the window, raising it and moving to its workspace.
+  pub fn activate(window: &WindowInfo) {
+    let conn = match Connection::open() {
+      Some(conn) => conn,
+      None => {
+        error!(
+          "Couldn't connect to the X server to activate {:?}",
+          window.title
+        );
+        return;
+      }
+    };
+
+    let mut event = XEvent {
+      client_message: XClientMessageEvent {
+        type_: CLIENT_MESSAGE,
+        serial: 0,
+        send_event: 1,
+        display: conn.display,
+        window: window.id,
+        message_type: conn.atom("_NET_ACTIVE_WINDOW"),
+        format: 32,
+        data: [SOURCE_PAGER, 0, 0, 0, 0],
+      },
+    };
+
+    debug!("Activating window {:#x} {:?}", window.id, window.title);
+    unsafe {
+      XSendEvent(
+        conn.display,
+        conn.root,
+        0,
+        SUBSTRUCTURE_REDIRECT_MASK | SUBSTRUCTURE_NOTIFY_MASK,
+        &mut event,
+      );
+      XFlush(conn.display);
+    }
+  }
+
+  /// The most recently used window of an app with the given `StartupWMClass`.
+  pub fn find_class(wm_class: &str) -> Option<WindowInfo> {
+    Self::list()
+      .into_iter()
+      .find(|window| window.has_class(wm_class))
+  }
+}
+
+/// Plays the window manager for the tests, it publishes `_NET_CLIENT_LIST` and receives the
+/// requests a window manager would. Only works on an X server without a window manager, e.g.
+/// Xvfb.
+#[doc(hidden)]
+pub struct FakeWindowManager {
+  conn: Connection,
+  windows: Vec<XWindow>,
+}
+
+impl FakeWindowManager {
+  pub fn new() -> Option<Self> {
+    let conn = Connection::open()?;
+    unsafe {
+      XSelectInput(conn.display, conn.root, SUBSTRUCTURE_REDIRECT_MASK);
+      XSync(conn.display, 0);
+    }
+
+    Some(Self {
+      conn,
+      windows: vec![],
+    })
+  }
+
+  /// Create a window with the given WM_NAME and WM_CLASS and add it to `_NET_CLIENT_LIST`.
+  pub fn create_window(&mut self, title: &str, instance: &str, class: &str) -> c_ulong {
+    let conn = &self.conn;
+    let window = unsafe { XCreateSimpleWindow(conn.display, conn.root, 0, 0, 100, 100, 0, 0, 0) };
+    let wm_class = format!("{}\0{}\0", instance, class);
+    let string = conn.atom("STRING");
+    self.set_property(window, "WM_NAME", string, 8, title.as_ptr(), title.len());
+    self.set_property(
+      window,
+      "WM_CLASS",
+      string,
+      8,
+      wm_class.as_ptr(),
+      wm_class.len(),
+    );
+
+    self.windows.push(window);
+    let clients = self.windows.clone();
+    self.set_property(
+      self.conn.root,
+      "_NET_CLIENT_LIST",
+      self.conn.atom("WINDOW"),
+      32,
+      clients.as_ptr() as *const c_uchar,
+      clients.len(),
+    );
+    unsafe { XSync(self.conn.display, 0) };
+
+    window
+  }
+
+  /// Wait for a `_NET_ACTIVE_WINDOW` request, returning the window and the message data.
+  pub fn wait_for_activation(&self, timeout: Duration) -> Option<(c_ulong, [c_long; 5])> {
+    let active_window = self.conn.atom("_NET_ACTIVE_WINDOW");
+    let start = Instant::now();
+
+    while start.elapsed() < timeout {
+      if unsafe { XPending(self.conn.display) } == 0 {
+        std::thread::sleep(Duration::from_millis(10));
+        continue;
+      }
+
+      let mut event = XEvent { pad: [0; 24] };
+      unsafe { XNextEvent(self.conn.display, &mut event) };
+      if unsafe { event.type_ } == CLIENT_MESSAGE {
+        let message = unsafe { event.client_message };
+        if message.message_type == active_window && message.format == 32 {
+          return Some((message.window, message.data));
+        }
+      }
+    }
+
+    None
+  }
+
+  /// Replace a property, `len` is the number of `format` sized items in `data`.
+  fn set_property(
+    &self,
+    window: XWindow,
+    property: &str,
+    kind: Atom,
+    format: c_int,
+    data: *const c_uchar,
+    len: usize,
+  ) {
+    unsafe {
+      XChangeProperty(
+        self.conn.display,
+        window,
+        self.conn.atom(property),
+        kind,
+        format,
+        PROP_MODE_REPLACE,
+        data,
+        len as c_int,
+      );
+    }
+  }
+}
+
+impl Drop for FakeWindowManager {
+  fn drop(&mut self) {
+    unsafe {
+      XDeleteProperty(
+        self.conn.display,
+        self.conn.root,
+        self.conn.atom("_NET_CLIENT_LIST"),
+      );
+      for window in &self.windows {
+        XDestroyWindow(self.conn.display, *window);
+      }
+      XSync(self.conn.display, 0);
+    }
+  }
+}
@@ -1,15 +1,22 @@
-use std::path::Path;
+use std::{
+  path::{Path, PathBuf},
+  time::Duration,
+};
 
+use dbus::blocking::Connection;
 use gtk::{
-  gdk::SELECTION_CLIPBOARD,
-  glib::{spawn_async, SpawnFlags},
+  gdk::{Display, SELECTION_CLIPBOARD},
+  gio::{self, AppLaunchContext, DesktopAppInfo},
+  glib::{self, spawn_async, SpawnFlags},
+  prelude::*,
   Clipboard,
 };
 use libc::setsid;
 
 use crate::{
   entry::app_entry::AppEntry,
-  fuzzy::{get_matching_blocks, get_score, MatchingBlocks},
+  fuzzy::{get_matching_blocks, get_score, get_score_and_blocks, MatchingBlocks},
+  provider::{session::SessionAction, windows::WindowInfo},
   script::Script,
 };
 
@@ -37,15 +44,23 @@ pub fn matches(query: &str, text: &str, min_score: usize) -> Option<MatchingBloc
   }
 }
 
-/// Checks if a user's query matches an apps description, name, and executable file.
+/// Checks if a user's query matches an apps description, name, keywords and executable file.
 pub fn matches_app(
   app: &AppEntry,
   query: &str,
   min_score: usize,
 ) -> Option<(MatchingBlocks, usize)> {
   let app_score = get_score(query, &app.name);
+  // keywords are aliases, a match ranks the app as if its name matched
+  let keyword_score = app
+    .keywords
+    .iter()
+    .map(|keyword| get_score(query, keyword))
+    .max()
+    .unwrap_or(0);
   let score = vec![
     app_score as f64,
+    keyword_score as f64,
     get_score(query, &shell_words::join(&app.exec)) as f64 * 0.8,
     get_score(query, &app.description) as f64 * 0.7,
   ]
@@ -55,7 +70,10 @@ pub fn matches_app(
   .unwrap();
 
   if score >= min_score {
-    Some((get_matching_blocks(query, &app.name), app_score))
+    Some((
+      get_matching_blocks(query, &app.name),
+      app_score.max(keyword_score),
+    ))
   } else {
     None
   }
@@ -84,6 +102,94 @@ pub fn matches_script(
   }
 }
 
+/// Checks if a user's query matches a file or folder name, or its path relative to the search
+/// directory it's in (e.g. `projects/dlauncher`). File matches are weighted slightly lower so apps
+/// with the same name are shown first.
+///
+/// Returns the name to show with the blocks matched in it, which is the relative path when that
+/// matched better than the name.
+pub fn matches_file(
+  path: &Path,
+  roots: &[PathBuf],
+  query: &str,
+  min_score: usize,
+) -> Option<(String, MatchingBlocks, usize)> {
+  let name = path.file_name()?.to_string_lossy().to_string();
+  let (name_score, name_blocks) = get_score_and_blocks(query, &name);
+  let name_score = (name_score as f64 * 0.9) as usize;
+
+  let relative = roots
+    .iter()
+    .find_map(|root| path.strip_prefix(root).ok())
+    .map(|relative| relative.to_string_lossy().to_string())
+    // files at the top of a search directory would only be scored twice
+    .filter(|relative| *relative != name);
+
+  let (name, blocks, score) = match relative {
+    Some(relative) => {
+      let (relative_score, relative_blocks) = get_score_and_blocks(query, &relative);
+      let relative_score = (relative_score as f64 * 0.8) as usize;
+      if relative_score > name_score {
+        (relative, relative_blocks, relative_score)
+      } else {
+        (name, name_blocks, name_score)
+      }
+    }
+    None => (name, name_blocks, name_score),
+  };
+
+  if score >= min_score {
+    Some((name, blocks, score))
+  } else {
+    None
+  }
+}
+
+/// Checks if a user's query matches a session action's name or one of its keywords.
+pub fn matches_session_action(
+  action: &SessionAction,
+  query: &str,
+  min_score: usize,
+) -> Option<(MatchingBlocks, usize)> {
+  let name_score = get_score(query, action.name());
+  let score = action
+    .keywords()
+    .iter()
+    .map(|keyword| (get_score(query, keyword) as f64 * 0.9) as usize)
+    .chain([name_score])
+    .max()
+    .unwrap();
+
+  if score >= min_score {
+    Some((get_matching_blocks(query, action.name()), score))
+  } else {
+    None
+  }
+}
+
+/// Checks if a user's query matches a window's title or WM_CLASS. Windows are weighted slightly lower
+/// than apps, since an app with a matching window already switches to it.
+pub fn matches_window(
+  window: &WindowInfo,
+  query: &str,
+  min_score: usize,
+) -> Option<(MatchingBlocks, usize)> {
+  let score = vec![
+    get_score(query, &window.title) as f64 * 0.9,
+    get_score(query, &window.class) as f64 * 0.8,
+  ]
+  .into_iter()
+  .map(|x| x as usize)
+  .max()
+  .unwrap();
+
+  if score >= min_score {
+    Some((get_matching_blocks(query, &window.title), score))
+  } else {
+    None
+  }
+}
+
 /// Initialize a logger, used for extensions.
 ///
 /// # Example
@@ -195,3 +301,59 @@ pub fn xdg_open(args: Vec<&str>, spawn_env_extra: Vec<&str>) {
 
   launch_detached(spawn_args, spawn_env_extra);
 }
+
+/// Show a file selected in its folder through the `org.freedesktop.FileManager1` D-Bus interface,
+/// falling back to opening the folder when no file manager implements it.
+pub fn show_in_file_manager(path: &Path) {
+  let uri = gio::File::for_path(path).uri().to_string();
+  let shown: Result<(), dbus::Error> = Connection::new_session().and_then(|conn| {
+    conn
+      .with_proxy(
+        "org.freedesktop.FileManager1",
+        "/org/freedesktop/FileManager1",
+        Duration::from_millis(2000),
+      )
+      .method_call("org.freedesktop.FileManager1", "ShowItems", (vec![uri], ""))
+  });
+
+  if shown.is_err() {
+    if let Some(parent) = path.parent() {
+      xdg_open(vec![&parent.display().to_string()], vec![]);
+    }
+  }
+}
+
+/// Create a launch context for starting applications, this lets the launched application know
+/// which screen and workspace to open on and provides it with a startup notification id.
+pub fn app_launch_context() -> Option<AppLaunchContext> {
+  let context = Display::default()?.app_launch_context()?;
+  context.set_timestamp(gtk::current_event_time());
+  // same as launch_detached, GDK_BACKEND is dlauncher specific and shouldn't leak into apps.
+  context.unsetenv("GDK_BACKEND");
+
+  Some(context.upcast())
+}
+
+/// Launch a desktop entry through GIO, which takes care of field codes (`%u`, `%f`, ...), `Path=`,
+/// startup notification and DBus activation. `uris` are substituted into the entry's field codes,
+/// local files can be passed as `file://` uris.
+///
+/// Apps that aren't DBus activatable are spawned in their own session (like
+/// [launch_detached](fn.launch_detached.html)) so they outlive dlauncher.
+pub fn launch_app(info: &DesktopAppInfo, uris: &[&str]) -> Result<(), glib::Error> {
+  let context = app_launch_context();
+
+  if info.boolean("DBusActivatable") {
+    info.launch_uris(uris, context.as_ref())
+  } else {
+    info.launch_uris_as_manager(
+      uris,
+      context.as_ref(),
+      SpawnFlags::SEARCH_PATH,
+      Some(Box::new(|| unsafe {
+        setsid();
+      })),
+      None,
+    )
+  }
+}
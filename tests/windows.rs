@@ -0,0 +1,39 @@
+//! Listing and activating windows against a real X server, the test plays the window manager by
+//! publishing `_NET_CLIENT_LIST` and receiving the `_NET_ACTIVE_WINDOW` request itself.
+//!
+//! It needs an X server without a window manager, run it under Xvfb:
+//! `xvfb-run cargo test --test windows -- --ignored`
+
+use std::time::Duration;
+
+use dlauncher::provider::windows::{FakeWindowManager, Windows};
+
+#[test]
+#[ignore = "needs an X server without a window manager, run it under Xvfb"]
+fn list_and_activate() {
+  let mut window_manager = FakeWindowManager::new().expect("couldn't connect to the X server");
+  let window = window_manager.create_window("dlauncher test window", "dltest", "Dltest");
+
+  let windows = Windows::list();
+  let found = windows
+    .iter()
+    .find(|info| info.id == window)
+    .expect("the test window isn't listed");
+  assert_eq!(found.title, "dlauncher test window");
+  assert_eq!(found.instance, "dltest");
+  assert_eq!(found.class, "Dltest");
+  assert!(found.has_class("DLTEST"));
+  assert_eq!(
+    Windows::find_class("dltest").map(|info| info.id),
+    Some(window)
+  );
+
+  Windows::activate(found);
+
+  let (requested, data) = window_manager
+    .wait_for_activation(Duration::from_secs(5))
+    .expect("no _NET_ACTIVE_WINDOW request was received");
+  assert_eq!(requested, window);
+  // sent as a pager so focus stealing prevention doesn't apply
+  assert_eq!(data[0], 2);
+}